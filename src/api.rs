@@ -1,3 +1,10 @@
+use crate::audit::AuditConfig;
+use crate::engagement::EngagementConfig;
+use crate::links::{self, LinkRules};
+use crate::recurring::{EvergreenQueue, RecurringConfig, RecurringJob};
+use crate::snapshots::SnapshotSchedule;
+use crate::template::TweetTemplate;
+use crate::text::{self, NormalizeConfig, NormalizedText};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64;
 use hmac::{Hmac, Mac};
@@ -9,7 +16,7 @@ use serde::de::DeserializeOwned;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const TWEETS_URL: &str = "https://api.x.com/2/tweets";
@@ -22,6 +29,7 @@ const MAX_RETRIES: u32 = 3;
 const RETRY_BASE_DELAY_MS: u64 = 1000;
 const CHUNK_SIZE: usize = 5 * 1024 * 1024; // 5MB per chunk
 const MAX_PROCESSING_WAIT_SECS: u64 = 600; // 10 minutes
+const MAX_CONVERSATION_PAGES: u32 = 10; // 100 tweets per page
 
 /// RFC 3986 unreserved characters — everything else gets percent-encoded.
 const RFC3986: &AsciiSet = &CONTROLS
@@ -50,7 +58,9 @@ type HmacSha1 = Hmac<sha1::Sha1>;
 
 // --- Config ---
 
-const ACCOUNT_CONFIG_FIELDS: &[(&str, fn(&AccountConfig) -> &str)] = &[
+type ConfigFieldGetter = fn(&AccountConfig) -> &str;
+
+const ACCOUNT_CONFIG_FIELDS: &[(&str, ConfigFieldGetter)] = &[
     ("api_key", |c| &c.api_key),
     ("api_key_secret", |c| &c.api_key_secret),
     ("access_token", |c| &c.access_token),
@@ -63,6 +73,35 @@ pub struct AccountConfig {
     pub api_key_secret: String,
     pub access_token: String,
     pub access_token_secret: String,
+    #[serde(default)]
+    pub normalize: NormalizeConfig,
+    #[serde(default)]
+    pub links: LinkRules,
+    /// Maximum weighted tweet length (280, or higher for X Premium accounts).
+    #[serde(default = "default_max_tweet_length")]
+    pub max_tweet_length: usize,
+    /// Refuse identical `post_tweet` calls within this many hours (0 disables).
+    #[serde(default = "default_duplicate_window_hours")]
+    pub duplicate_window_hours: u64,
+    /// Appended to tweets rendered from templates (unless the template opts out).
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Refuse tweets that mention missing, suspended or protected handles
+    /// (when false, post anyway and warn).
+    #[serde(default = "default_true")]
+    pub strict_mentions: bool,
+}
+
+fn default_max_tweet_length() -> usize {
+    280
+}
+
+fn default_duplicate_window_hours() -> u64 {
+    24
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl fmt::Debug for AccountConfig {
@@ -72,6 +111,12 @@ impl fmt::Debug for AccountConfig {
             .field("api_key_secret", &"***REDACTED***")
             .field("access_token", &"***REDACTED***")
             .field("access_token_secret", &"***REDACTED***")
+            .field("normalize", &self.normalize)
+            .field("links", &self.links)
+            .field("max_tweet_length", &self.max_tweet_length)
+            .field("duplicate_window_hours", &self.duplicate_window_hours)
+            .field("signature", &self.signature)
+            .field("strict_mentions", &self.strict_mentions)
             .finish()
     }
 }
@@ -90,6 +135,18 @@ impl AccountConfig {
 pub struct AppConfig {
     pub accounts: HashMap<String, AccountConfig>,
     pub default_account: String,
+    /// Directory for local state (thread jobs and other stores).
+    pub data_dir: PathBuf,
+    /// Named tweet templates from `[templates.<name>]`.
+    pub templates: HashMap<String, TweetTemplate>,
+    /// `[[recurring]]` jobs, `[evergreen.<name>]` queues and `holidays`.
+    pub recurring: RecurringConfig,
+    /// `[audit]` section; the audit log is off when it is missing.
+    pub audit: Option<AuditConfig>,
+    /// `[follower_snapshots]` section; no scheduled snapshots when it is missing.
+    pub follower_snapshots: Option<SnapshotSchedule>,
+    /// `[engagement]` section; metrics aren't tracked in the background when it is missing.
+    pub engagement: Option<EngagementConfig>,
 }
 
 impl AppConfig {
@@ -97,7 +154,19 @@ impl AppConfig {
         #[derive(Deserialize)]
         struct RawConfig {
             default_account: Option<String>,
+            data_dir: Option<String>,
             accounts: HashMap<String, AccountConfig>,
+            #[serde(default)]
+            templates: HashMap<String, TweetTemplate>,
+            #[serde(default)]
+            recurring: Vec<RecurringJob>,
+            #[serde(default)]
+            evergreen: HashMap<String, EvergreenQueue>,
+            #[serde(default)]
+            holidays: Vec<String>,
+            audit: Option<AuditConfig>,
+            follower_snapshots: Option<SnapshotSchedule>,
+            engagement: Option<EngagementConfig>,
         }
 
         let raw: RawConfig = toml::from_str(content)
@@ -133,9 +202,56 @@ impl AppConfig {
             ));
         };
 
+        for (name, template) in &raw.templates {
+            template
+                .placeholders()
+                .map_err(|e| format!("Template '{name}': {e}"))?;
+        }
+
+        let account_names: Vec<&str> = raw.accounts.keys().map(|s| s.as_str()).collect();
+        let max_lengths: HashMap<&str, usize> = raw
+            .accounts
+            .iter()
+            .map(|(name, acct)| (name.as_str(), acct.max_tweet_length))
+            .collect();
+        let recurring = RecurringConfig::from_raw(
+            raw.recurring,
+            raw.evergreen,
+            &raw.holidays,
+            &max_lengths,
+            &default_account,
+        )?;
+        if let Some(snapshots) = &raw.follower_snapshots {
+            snapshots
+                .validate(&account_names)
+                .map_err(|e| format!("[follower_snapshots]: {e}"))?;
+        }
+        if let Some(audit) = &raw.audit {
+            audit.validate().map_err(|e| format!("[audit]: {e}"))?;
+        }
+        if let Some(engagement) = &raw.engagement {
+            engagement
+                .validate()
+                .map_err(|e| format!("[engagement]: {e}"))?;
+        }
+
+        let data_dir = raw.data_dir.map(PathBuf::from).unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/root".into());
+            PathBuf::from(home)
+                .join(".local")
+                .join("share")
+                .join("mcp-server-post-x")
+        });
+
         Ok(AppConfig {
             accounts: raw.accounts,
             default_account,
+            data_dir,
+            templates: raw.templates,
+            recurring,
+            audit: raw.audit,
+            follower_snapshots: raw.follower_snapshots,
+            engagement: raw.engagement,
         })
     }
 }
@@ -175,6 +291,7 @@ struct MediaInfo {
     max_size: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaAttachment {
     pub path: String,
     pub alt_text: Option<String>,
@@ -200,6 +317,34 @@ struct TweetBody {
     media: Option<TweetMedia>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reply: Option<TweetReply>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_tweet_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edit_options: Option<TweetEditOptions>,
+}
+
+/// Other tweets a new tweet points at.
+#[derive(Default, Clone, Copy)]
+struct TweetRefs<'a> {
+    reply_to: Option<&'a str>,
+    quote: Option<&'a str>,
+    edit_of: Option<&'a str>,
+}
+
+impl TweetBody {
+    fn new(text: String, media_ids: Option<Vec<String>>, refs: TweetRefs<'_>) -> Self {
+        Self {
+            text,
+            media: media_ids.map(|ids| TweetMedia { media_ids: ids }),
+            reply: refs.reply_to.map(|id| TweetReply {
+                in_reply_to_tweet_id: id.to_string(),
+            }),
+            quote_tweet_id: refs.quote.map(str::to_string),
+            edit_options: refs.edit_of.map(|id| TweetEditOptions {
+                previous_post_id: id.to_string(),
+            }),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -212,6 +357,11 @@ struct TweetReply {
     in_reply_to_tweet_id: String,
 }
 
+#[derive(Serialize)]
+struct TweetEditOptions {
+    previous_post_id: String,
+}
+
 #[derive(Deserialize)]
 struct TweetResponse {
     data: TweetData,
@@ -262,13 +412,37 @@ pub struct MeData {
 pub struct PostResult {
     pub tweet_id: String,
     pub url: String,
+    /// Text as posted, after normalization and link rules.
+    pub text: String,
+    /// Normalization and link rewriting steps that changed the tweet before posting.
+    pub transformations: Vec<String>,
 }
 
 pub struct ThreadResult {
     pub posted: Vec<PostResult>,
+    /// Index into the thread of the tweet that failed to post. `None` when the
+    /// thread stopped before posting anything (pre-flight or media upload).
+    pub failed_index: Option<usize>,
     pub error: Option<String>,
 }
 
+/// An X API request that a dry run reports instead of sending.
+pub struct RequestPreview {
+    pub method: &'static str,
+    pub url: String,
+    /// JSON body, or `Null` for requests without one.
+    pub body: serde_json::Value,
+}
+
+pub struct TweetPreview {
+    pub request: RequestPreview,
+    /// Media files that would be uploaded before the tweet is posted.
+    pub uploads: Vec<String>,
+    pub weighted_length: usize,
+    pub max_length: usize,
+    pub transformations: Vec<String>,
+}
+
 // --- Follows response types ---
 
 #[derive(Deserialize)]
@@ -295,9 +469,7 @@ pub struct PublicMetrics {
 
 #[derive(Deserialize)]
 pub struct FollowsMeta {
-    pub result_count: Option<u32>,
     pub next_token: Option<String>,
-    pub previous_token: Option<String>,
 }
 
 pub struct FollowsResult {
@@ -310,6 +482,7 @@ pub struct FollowsResult {
 #[derive(Deserialize)]
 struct UserLookupResponse {
     data: Option<UserProfile>,
+    errors: Option<Vec<LookupError>>,
 }
 
 #[derive(Deserialize)]
@@ -379,6 +552,7 @@ struct SendDmData {
     dm_event_id: String,
 }
 
+#[allow(dead_code)]
 pub struct DmEventResult {
     pub id: String,
     pub event_type: String,
@@ -406,7 +580,11 @@ struct SearchTweet {
     text: String,
     author_id: Option<String>,
     created_at: Option<String>,
+    conversation_id: Option<String>,
     public_metrics: Option<TweetPublicMetrics>,
+    referenced_tweets: Option<Vec<ReferencedTweetRef>>,
+    attachments: Option<TweetAttachments>,
+    entities: Option<TweetEntities>,
 }
 
 #[derive(Deserialize)]
@@ -414,17 +592,67 @@ struct TweetPublicMetrics {
     like_count: u64,
     retweet_count: u64,
     reply_count: u64,
+    #[serde(default)]
+    quote_count: u64,
+}
+
+#[derive(Deserialize)]
+struct ReferencedTweetRef {
+    #[serde(rename = "type")]
+    kind: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TweetAttachments {
+    media_keys: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct TweetEntities {
+    urls: Option<Vec<EntityUrl>>,
+    hashtags: Option<Vec<EntityTag>>,
+    mentions: Option<Vec<EntityMention>>,
+}
+
+#[derive(Deserialize)]
+struct EntityUrl {
+    url: String,
+    expanded_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EntityTag {
+    tag: String,
+}
+
+#[derive(Deserialize)]
+struct EntityMention {
+    username: String,
 }
 
 #[derive(Deserialize)]
 struct SearchIncludes {
     users: Option<Vec<SearchUser>>,
+    media: Option<Vec<SearchMedia>>,
+    tweets: Option<Vec<SearchTweet>>,
 }
 
 #[derive(Deserialize)]
 struct SearchUser {
     id: String,
     username: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchMedia {
+    media_key: String,
+    #[serde(rename = "type")]
+    media_type: String,
+    url: Option<String>,
+    preview_image_url: Option<String>,
+    alt_text: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -432,19 +660,118 @@ struct PaginationMeta {
     next_token: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct TweetLookupResponse {
+    data: Option<Vec<SearchTweet>>,
+    includes: Option<SearchIncludes>,
+    errors: Option<Vec<LookupError>>,
+}
+
+#[derive(Deserialize)]
+struct LookupError {
+    value: Option<String>,
+    title: Option<String>,
+    detail: Option<String>,
+}
+
 pub struct SearchResult {
     pub tweets: Vec<SearchTweetResult>,
     pub next_token: Option<String>,
 }
 
+pub struct ConversationResult {
+    pub tweets: Vec<SearchTweetResult>,
+    /// True when the page limit was hit before the search ran out of results.
+    pub truncated: bool,
+}
+
+pub struct TweetLookupResult {
+    pub tweets: Vec<SearchTweetResult>,
+    /// IDs that could not be returned (deleted, protected, not found), with the reason.
+    pub errors: Vec<String>,
+}
+
 pub struct SearchTweetResult {
     pub id: String,
     pub text: String,
     pub username: Option<String>,
+    pub author_name: Option<String>,
+    pub author_id: Option<String>,
     pub created_at: Option<String>,
+    pub conversation_id: Option<String>,
     pub like_count: u64,
     pub retweet_count: u64,
     pub reply_count: u64,
+    pub quote_count: u64,
+    pub referenced_tweets: Vec<ReferencedTweetResult>,
+    pub media: Vec<TweetMediaResult>,
+    pub urls: Vec<TweetUrlResult>,
+    pub hashtags: Vec<String>,
+    pub mentions: Vec<String>,
+}
+
+// --- Tweet metrics types ---
+
+#[derive(Deserialize)]
+struct MetricsLookupResponse {
+    data: Option<Vec<MetricsTweet>>,
+    errors: Option<Vec<LookupError>>,
+}
+
+#[derive(Deserialize)]
+struct MetricsTweet {
+    id: String,
+    public_metrics: Option<MetricCounts>,
+    non_public_metrics: Option<MetricCounts>,
+    organic_metrics: Option<MetricCounts>,
+}
+
+/// Counts from `public_metrics`, `non_public_metrics` or `organic_metrics`.
+/// Each object has a different subset of fields, so all are optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetricCounts {
+    pub impression_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub retweet_count: Option<u64>,
+    pub reply_count: Option<u64>,
+    pub quote_count: Option<u64>,
+    pub bookmark_count: Option<u64>,
+    pub url_link_clicks: Option<u64>,
+    pub user_profile_clicks: Option<u64>,
+}
+
+pub struct TweetMetrics {
+    pub id: String,
+    pub public: MetricCounts,
+    pub non_public: Option<MetricCounts>,
+    pub organic: Option<MetricCounts>,
+}
+
+pub struct TweetMetricsResult {
+    pub tweets: Vec<TweetMetrics>,
+    /// IDs that X returned an error for, with the reason.
+    pub errors: Vec<(String, String)>,
+}
+
+pub struct ReferencedTweetResult {
+    /// `replied_to`, `quoted` or `retweeted`.
+    pub kind: String,
+    pub id: String,
+    pub username: Option<String>,
+    pub text: Option<String>,
+}
+
+pub struct TweetUrlResult {
+    /// The t.co link as it appears in the tweet text.
+    pub short_url: String,
+    pub expanded_url: String,
+}
+
+pub struct TweetMediaResult {
+    pub media_type: String,
+    /// Full image URL for photos, preview image for videos and GIFs.
+    pub url: Option<String>,
+    pub alt_text: Option<String>,
 }
 
 // --- XClient implementation ---
@@ -454,6 +781,10 @@ impl XClient {
         Self { config, http }
     }
 
+    pub fn config(&self) -> &AccountConfig {
+        &self.config
+    }
+
     // --- Shared helpers: response checking, URL building, GET/POST ---
 
     async fn check_response(&self, resp: reqwest::Response) -> Result<reqwest::Response, String> {
@@ -546,44 +877,120 @@ impl XClient {
     }
 
     fn map_tweet_list(response: TweetListResponse) -> SearchResult {
-        let user_map: std::collections::HashMap<String, String> = response
-            .includes
-            .and_then(|inc| inc.users)
+        SearchResult {
+            tweets: Self::map_tweets(response.data, response.includes),
+            next_token: response.meta.and_then(|m| m.next_token),
+        }
+    }
+
+    fn map_tweets(
+        data: Option<Vec<SearchTweet>>,
+        includes: Option<SearchIncludes>,
+    ) -> Vec<SearchTweetResult> {
+        let includes = includes.unwrap_or(SearchIncludes {
+            users: None,
+            media: None,
+            tweets: None,
+        });
+        let user_map: HashMap<String, SearchUser> = includes
+            .users
             .unwrap_or_default()
             .into_iter()
-            .map(|u| (u.id, u.username))
+            .map(|u| (u.id.clone(), u))
             .collect();
-
-        let tweets = response
-            .data
+        let media_map: HashMap<String, SearchMedia> = includes
+            .media
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| (m.media_key.clone(), m))
+            .collect();
+        let tweet_map: HashMap<String, SearchTweet> = includes
+            .tweets
             .unwrap_or_default()
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+
+        data.unwrap_or_default()
             .into_iter()
             .map(|t| {
-                let username = t
-                    .author_id
-                    .as_ref()
-                    .and_then(|aid| user_map.get(aid))
-                    .cloned();
-                let (like_count, retweet_count, reply_count) =
-                    t.public_metrics.map_or((0, 0, 0), |m| {
-                        (m.like_count, m.retweet_count, m.reply_count)
+                let author = t.author_id.as_ref().and_then(|aid| user_map.get(aid));
+                let (like_count, retweet_count, reply_count, quote_count) =
+                    t.public_metrics.map_or((0, 0, 0, 0), |m| {
+                        (m.like_count, m.retweet_count, m.reply_count, m.quote_count)
                     });
+                let referenced_tweets = t
+                    .referenced_tweets
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|r| {
+                        let included = tweet_map.get(&r.id);
+                        ReferencedTweetResult {
+                            username: included
+                                .and_then(|inc| inc.author_id.as_ref())
+                                .and_then(|aid| user_map.get(aid))
+                                .map(|u| u.username.clone()),
+                            text: included.map(|inc| inc.text.clone()),
+                            kind: r.kind,
+                            id: r.id,
+                        }
+                    })
+                    .collect();
+                let media = t
+                    .attachments
+                    .and_then(|a| a.media_keys)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|key| media_map.get(key))
+                    .map(|m| TweetMediaResult {
+                        media_type: m.media_type.clone(),
+                        url: m.url.clone().or_else(|| m.preview_image_url.clone()),
+                        alt_text: m.alt_text.clone(),
+                    })
+                    .collect();
+                let entities = t.entities.unwrap_or(TweetEntities {
+                    urls: None,
+                    hashtags: None,
+                    mentions: None,
+                });
                 SearchTweetResult {
                     id: t.id,
                     text: t.text,
-                    username,
+                    username: author.map(|u| u.username.clone()),
+                    author_name: author.and_then(|u| u.name.clone()),
+                    author_id: t.author_id,
                     created_at: t.created_at,
+                    conversation_id: t.conversation_id,
                     like_count,
                     retweet_count,
                     reply_count,
+                    quote_count,
+                    referenced_tweets,
+                    media,
+                    urls: entities
+                        .urls
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|u| TweetUrlResult {
+                            expanded_url: u.expanded_url.unwrap_or_else(|| u.url.clone()),
+                            short_url: u.url,
+                        })
+                        .collect(),
+                    hashtags: entities
+                        .hashtags
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|h| h.tag)
+                        .collect(),
+                    mentions: entities
+                        .mentions
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|m| m.username)
+                        .collect(),
                 }
             })
-            .collect();
-
-        SearchResult {
-            tweets,
-            next_token: response.meta.and_then(|m| m.next_token),
-        }
+            .collect()
     }
 
     // --- Public API methods ---
@@ -666,65 +1073,68 @@ impl XClient {
         media: &[MediaAttachment],
         media_ids: Option<&[String]>,
         reply_to: Option<&str>,
+        quote: Option<&str>,
         username: &str,
     ) -> Result<PostResult, String> {
-        self.validate_tweet_text(text)?;
+        let refs = TweetRefs {
+            reply_to,
+            quote,
+            edit_of: None,
+        };
+        self.create_tweet(text, media, media_ids, refs, username)
+            .await
+    }
 
-        let resolved_ids = if !media.is_empty() {
-            // Pre-flight validation: check all files before uploading any
-            let mut infos = Vec::new();
-            for attachment in media {
-                let path = Path::new(&attachment.path);
-                if !path.exists() {
-                    return Err(format!("File not found: {}", attachment.path));
-                }
-                let info = media_info_from_path(path)?;
-                let file_size = std::fs::metadata(path)
-                    .map_err(|e| format!("Cannot read file metadata: {e}"))?
-                    .len();
-                if file_size > info.max_size {
-                    return Err(format!(
-                        "File too large: {} ({} bytes, max {}MB)",
-                        attachment.path,
-                        file_size,
-                        info.max_size / (1024 * 1024)
-                    ));
-                }
-                if attachment.alt_text.is_some() && info.media_type == MediaType::Video {
-                    return Err("Alt text is not supported for videos".into());
+    /// Publish a new version of `previous_id` via `edit_options`. X only allows
+    /// edits within a limited window and a limited number of times per post.
+    pub async fn edit_tweet(
+        &self,
+        previous_id: &str,
+        text: &str,
+        media: &[MediaAttachment],
+        media_ids: Option<&[String]>,
+        username: &str,
+    ) -> Result<PostResult, String> {
+        let refs = TweetRefs {
+            edit_of: Some(previous_id),
+            ..Default::default()
+        };
+        self.create_tweet(text, media, media_ids, refs, username)
+            .await
+            .map_err(|e| {
+                let lower = e.to_lowercase();
+                let rejected =
+                    e.starts_with("X API error (403") || e.starts_with("X API error (400");
+                if rejected && lower.contains("edit") {
+                    format!(
+                        "Tweet {previous_id} can no longer be edited: its edit window or \
+                         edit count has run out (X allows edits for a limited time and a \
+                         limited number of times per post). Details: {e}"
+                    )
+                } else {
+                    e
                 }
-                infos.push(info);
-            }
-            validate_media_combination(&infos)?;
+            })
+    }
 
-            let mut ids = Vec::new();
-            for attachment in media {
-                let result = self
-                    .upload_media(&attachment.path, attachment.alt_text.as_deref())
-                    .await?;
-                if result.state != "succeeded" {
-                    return Err(format!(
-                        "Media processing {}: {}",
-                        result.state, attachment.path
-                    ));
-                }
-                ids.push(result.media_id);
-            }
-            Some(ids)
-        } else if let Some(ids) = media_ids {
-            Some(ids.to_vec())
+    async fn create_tweet(
+        &self,
+        text: &str,
+        media: &[MediaAttachment],
+        media_ids: Option<&[String]>,
+        refs: TweetRefs<'_>,
+        username: &str,
+    ) -> Result<PostResult, String> {
+        let normalized = self.prepare_text(text);
+        self.validate_tweet_text(&normalized.text)?;
+
+        let resolved_ids = if !media.is_empty() {
+            Some(self.upload_attachments(media).await?)
         } else {
-            None
+            media_ids.map(|ids| ids.to_vec())
         };
 
-        let text = text.replace('\u{2014}', "-").replace('\u{2013}', "-");
-        let body = TweetBody {
-            text,
-            media: resolved_ids.map(|ids| TweetMedia { media_ids: ids }),
-            reply: reply_to.map(|id| TweetReply {
-                in_reply_to_tweet_id: id.to_string(),
-            }),
-        };
+        let body = TweetBody::new(normalized.text.clone(), resolved_ids, refs);
 
         let resp = self.post_json_raw(TWEETS_URL, &body).await?;
         let tweet: TweetResponse = resp
@@ -735,48 +1145,145 @@ impl XClient {
         Ok(PostResult {
             url: format!("https://x.com/{}/status/{}", username, tweet.data.id),
             tweet_id: tweet.data.id,
+            text: normalized.text,
+            transformations: normalized.applied,
         })
     }
 
+    /// Validate every attachment, then upload them all and wait for processing.
+    async fn upload_attachments(&self, media: &[MediaAttachment]) -> Result<Vec<String>, String> {
+        validate_attachments(media)?;
+        self.upload_validated(media).await
+    }
+
+    /// Upload attachments that already passed `validate_attachments`, failing
+    /// unless processing succeeds for every file.
+    async fn upload_validated(&self, media: &[MediaAttachment]) -> Result<Vec<String>, String> {
+        let mut ids = Vec::new();
+        for attachment in media {
+            let result = self
+                .upload_media(&attachment.path, attachment.alt_text.as_deref())
+                .await?;
+            if result.state != "succeeded" {
+                return Err(format!(
+                    "Media processing {}: {}",
+                    result.state, attachment.path
+                ));
+            }
+            ids.push(result.media_id);
+        }
+        Ok(ids)
+    }
+
+    /// Post tweets as a chain of replies. When `reply_to` is set, the first
+    /// tweet replies to it, which continues an existing thread or conversation.
+    /// `on_posted` is called with the index and result of each posted tweet.
     pub async fn post_thread(
         &self,
         tweets: &[(String, Vec<MediaAttachment>)],
+        reply_to: Option<&str>,
+        username: &str,
+        on_posted: impl FnMut(usize, &PostResult),
+    ) -> ThreadResult {
+        self.post_thread_from(tweets, 0, reply_to, username, on_posted)
+            .await
+    }
+
+    /// Post `tweets[start..]` as a chain of replies, numbering progress and
+    /// errors against the whole thread. Used to resume a partially posted thread.
+    pub async fn post_thread_from(
+        &self,
+        tweets: &[(String, Vec<MediaAttachment>)],
+        start: usize,
+        reply_to: Option<&str>,
         username: &str,
+        mut on_posted: impl FnMut(usize, &PostResult),
     ) -> ThreadResult {
         if tweets.is_empty() {
             return ThreadResult {
                 posted: vec![],
+                failed_index: None,
                 error: Some("Thread must contain at least one tweet".into()),
             };
         }
         if tweets.len() > MAX_THREAD_LENGTH {
             return ThreadResult {
                 posted: vec![],
+                failed_index: None,
                 error: Some(format!(
                     "Thread exceeds maximum of {MAX_THREAD_LENGTH} tweets"
                 )),
             };
         }
 
+        // When resuming, tweets before `start` are already live; say so rather
+        // than claim the thread is untouched.
+        let already_live = match start {
+            0 => "nothing was posted".to_string(),
+            1 => "nothing new was posted; tweet 1 is already live".to_string(),
+            _ => format!("nothing new was posted; tweets 1-{start} are already live"),
+        };
+        let failed_before_posting = |i: usize, stage: &str, e: String| ThreadResult {
+            posted: vec![],
+            failed_index: None,
+            error: Some(format!(
+                "Tweet {} of {} failed {stage} ({already_live}): {e}",
+                i + 1,
+                tweets.len()
+            )),
+        };
+
+        // Pre-flight every tweet so a bad file never leaves a partial thread.
+        for (i, (text, media)) in tweets.iter().enumerate().skip(start) {
+            let normalized = self.prepare_text(text);
+            if let Err(e) = self
+                .validate_tweet_text(&normalized.text)
+                .and_then(|_| validate_attachments(media))
+            {
+                return failed_before_posting(i, "pre-flight validation", e);
+            }
+        }
+
+        // Upload all media and wait for processing before the first tweet goes out.
+        let mut media_ids: Vec<Option<Vec<String>>> = vec![None; tweets.len()];
+        for (i, (_, media)) in tweets.iter().enumerate().skip(start) {
+            if media.is_empty() {
+                continue;
+            }
+            match self.upload_validated(media).await {
+                Ok(ids) => media_ids[i] = Some(ids),
+                Err(e) => return failed_before_posting(i, "media upload", e),
+            }
+        }
+
         let mut posted = Vec::new();
-        let mut reply_to: Option<String> = None;
+        let mut reply_to: Option<String> = reply_to.map(str::to_string);
 
-        for (i, (text, media)) in tweets.iter().enumerate() {
-            if i > 0 {
+        for (i, (text, _)) in tweets.iter().enumerate().skip(start) {
+            if i > start {
                 tokio::time::sleep(Duration::from_millis(500)).await;
             }
 
             match self
-                .post_tweet(text, media, None, reply_to.as_deref(), username)
+                .post_tweet(
+                    text,
+                    &[],
+                    media_ids[i].as_deref(),
+                    reply_to.as_deref(),
+                    None,
+                    username,
+                )
                 .await
             {
                 Ok(post) => {
                     reply_to = Some(post.tweet_id.clone());
+                    on_posted(i, &post);
                     posted.push(post);
                 }
                 Err(e) => {
                     return ThreadResult {
                         posted,
+                        failed_index: Some(i),
                         error: Some(format!(
                             "Tweet {} of {} failed: {e}",
                             i + 1,
@@ -789,10 +1296,161 @@ impl XClient {
 
         ThreadResult {
             posted,
+            failed_index: None,
             error: None,
         }
     }
 
+    /// Delete posted tweets newest first. Returns each tweet ID with the
+    /// delete outcome, in the order the deletes were attempted.
+    pub async fn rollback_thread(
+        &self,
+        posted: &[PostResult],
+    ) -> Vec<(String, Result<(), String>)> {
+        let mut outcomes = Vec::new();
+        for (i, post) in posted.iter().rev().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            let outcome = match self.delete_tweet(&post.tweet_id).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("X reported the tweet was not deleted".to_string()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &outcome {
+                tracing::warn!("Rollback: failed to delete {}: {e}", post.tweet_id);
+            }
+            outcomes.push((post.tweet_id.clone(), outcome));
+        }
+        outcomes
+    }
+
+    // --- Request previews (dry run) ---
+    //
+    // The real endpoints build their requests with the same helpers, so a
+    // preview shows exactly what would be sent.
+
+    pub fn delete_tweet_request(tweet_id: &str) -> RequestPreview {
+        RequestPreview {
+            method: "DELETE",
+            url: format!("{TWEETS_URL}/{tweet_id}"),
+            body: serde_json::Value::Null,
+        }
+    }
+
+    pub fn follow_request(user_id: &str, target_user_id: &str) -> RequestPreview {
+        RequestPreview {
+            method: "POST",
+            url: format!("https://api.x.com/2/users/{user_id}/following"),
+            body: serde_json::json!({ "target_user_id": target_user_id }),
+        }
+    }
+
+    pub fn send_dm_request(conversation_id: &str, text: &str) -> RequestPreview {
+        RequestPreview {
+            method: "POST",
+            url: format!("https://api.x.com/2/dm_conversations/{conversation_id}/messages"),
+            body: serde_json::json!({ "text": text }),
+        }
+    }
+
+    /// Run every local check `post_tweet` performs and return the request it
+    /// would send. Media that would be uploaded appears as `<media_id:PATH>`.
+    pub fn preview_tweet(
+        &self,
+        text: &str,
+        media: &[MediaAttachment],
+        media_ids: Option<&[String]>,
+        reply_to: Option<&str>,
+        quote: Option<&str>,
+    ) -> Result<TweetPreview, String> {
+        let normalized = self.prepare_text(text);
+        self.validate_tweet_text(&normalized.text)?;
+        validate_attachments(media)?;
+
+        let mut uploads = Vec::new();
+        for attachment in media {
+            let path = Path::new(&attachment.path);
+            let info = media_info_from_path(path)?;
+            let size = std::fs::metadata(path)
+                .map_err(|e| format!("Cannot read file metadata: {e}"))?
+                .len();
+            uploads.push(format!(
+                "{} ({}, {} bytes{})",
+                attachment.path,
+                info.media_type,
+                size,
+                if attachment.alt_text.is_some() {
+                    ", with alt text"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        let resolved_ids = if !media.is_empty() {
+            Some(
+                media
+                    .iter()
+                    .map(|a| format!("<media_id:{}>", a.path))
+                    .collect(),
+            )
+        } else {
+            media_ids.map(|ids| ids.to_vec())
+        };
+
+        let weighted_length = text::weighted_length(&normalized.text);
+        let refs = TweetRefs {
+            reply_to,
+            quote,
+            edit_of: None,
+        };
+        let body = TweetBody::new(normalized.text, resolved_ids, refs);
+        Ok(TweetPreview {
+            request: RequestPreview {
+                method: "POST",
+                url: TWEETS_URL.to_string(),
+                body: serde_json::to_value(&body)
+                    .map_err(|e| format!("Failed to serialize tweet body: {e}"))?,
+            },
+            uploads,
+            weighted_length,
+            max_length: self.config.max_tweet_length,
+            transformations: normalized.applied,
+        })
+    }
+
+    /// Preview every tweet of a thread. Replies to tweets that do not exist yet
+    /// appear as `<id of tweet N>`.
+    pub fn preview_thread(
+        &self,
+        tweets: &[(String, Vec<MediaAttachment>)],
+        reply_to: Option<&str>,
+    ) -> Result<Vec<TweetPreview>, String> {
+        if tweets.is_empty() {
+            return Err("Thread must contain at least one tweet".into());
+        }
+        if tweets.len() > MAX_THREAD_LENGTH {
+            return Err(format!(
+                "Thread exceeds maximum of {MAX_THREAD_LENGTH} tweets"
+            ));
+        }
+
+        let mut previews = Vec::new();
+        for (i, (text, media)) in tweets.iter().enumerate() {
+            let parent = if i == 0 {
+                reply_to.map(str::to_string)
+            } else {
+                Some(format!("<id of tweet {i}>"))
+            };
+            let preview = self
+                .preview_tweet(text, media, None, parent.as_deref(), None)
+                .map_err(|e| format!("Tweet {} of {}: {e}", i + 1, tweets.len()))?;
+            previews.push(preview);
+        }
+        Ok(previews)
+    }
+
     // --- Follows lookup ---
 
     pub async fn get_followers(
@@ -816,26 +1474,57 @@ impl XClient {
     }
 
     pub async fn get_all_followers(&self, user_id: &str) -> Result<Vec<UserSummary>, String> {
-        let url = format!("https://api.x.com/2/users/{user_id}/followers");
-        self.get_all_follows(&url).await
+        let mut users = Vec::new();
+        self.stream_followers(user_id, |page| {
+            users.extend(page);
+            Ok(())
+        })
+        .await?;
+        Ok(users)
     }
 
     pub async fn get_all_following(&self, user_id: &str) -> Result<Vec<UserSummary>, String> {
+        let mut users = Vec::new();
+        self.stream_following(user_id, |page| {
+            users.extend(page);
+            Ok(())
+        })
+        .await?;
+        Ok(users)
+    }
+
+    /// Like `get_all_followers`, but hands each page to `on_page` as it
+    /// arrives instead of collecting them. Returns the number of users.
+    pub async fn stream_followers(
+        &self,
+        user_id: &str,
+        on_page: impl FnMut(Vec<UserSummary>) -> Result<(), String>,
+    ) -> Result<usize, String> {
+        let url = format!("https://api.x.com/2/users/{user_id}/followers");
+        self.stream_follows(&url, on_page).await
+    }
+
+    pub async fn stream_following(
+        &self,
+        user_id: &str,
+        on_page: impl FnMut(Vec<UserSummary>) -> Result<(), String>,
+    ) -> Result<usize, String> {
         let url = format!("https://api.x.com/2/users/{user_id}/following");
-        self.get_all_follows(&url).await
+        self.stream_follows(&url, on_page).await
     }
 
-    async fn get_all_follows(&self, base_url: &str) -> Result<Vec<UserSummary>, String> {
-        let mut all_users = Vec::new();
+    async fn stream_follows(
+        &self,
+        base_url: &str,
+        mut on_page: impl FnMut(Vec<UserSummary>) -> Result<(), String>,
+    ) -> Result<usize, String> {
+        let mut total = 0;
         let mut next_token: Option<String> = None;
         let mut page = 0u32;
 
         loop {
             page += 1;
-            tracing::info!(
-                "get_all_follows: fetching page {page}, collected {} so far",
-                all_users.len()
-            );
+            tracing::info!("get_all_follows: fetching page {page}, collected {total} so far");
             let result = self
                 .get_follows(base_url, 100, next_token.as_deref())
                 .await?;
@@ -843,7 +1532,8 @@ impl XClient {
                 "get_all_follows: page {page} returned {} users",
                 result.users.len()
             );
-            all_users.extend(result.users);
+            total += result.users.len();
+            on_page(result.users)?;
 
             match result.next_token {
                 Some(token) => next_token = Some(token),
@@ -854,8 +1544,8 @@ impl XClient {
             tokio::time::sleep(Duration::from_millis(200)).await;
         }
 
-        tracing::info!("get_all_follows: done, total {} users", all_users.len());
-        Ok(all_users)
+        tracing::info!("get_all_follows: done, total {total} users");
+        Ok(total)
     }
 
     async fn get_follows(
@@ -904,7 +1594,16 @@ impl XClient {
         );
 
         let response: UserLookupResponse = self.get_json(base_url, &params).await?;
-        response.data.ok_or_else(|| "User not found".to_string())
+        response.data.ok_or_else(|| {
+            // X reports missing and suspended users as errors in a 200 response.
+            match response.errors.unwrap_or_default().into_iter().next() {
+                Some(e) => match e.detail.or(e.title) {
+                    Some(detail) => format!("User not found: {detail}"),
+                    None => "User not found".to_string(),
+                },
+                None => "User not found".to_string(),
+            }
+        })
     }
 
     // --- Likes ---
@@ -920,7 +1619,7 @@ impl XClient {
     }
 
     pub async fn delete_tweet(&self, tweet_id: &str) -> Result<bool, String> {
-        self.delete_bool(&format!("{TWEETS_URL}/{tweet_id}")).await
+        self.delete_bool(&Self::delete_tweet_request(tweet_id).url).await
     }
 
     pub async fn retweet(&self, user_id: &str, tweet_id: &str) -> Result<bool, String> {
@@ -936,10 +1635,8 @@ impl XClient {
     // --- Follows ---
 
     pub async fn follow_user(&self, user_id: &str, target_user_id: &str) -> Result<bool, String> {
-        let url = format!("https://api.x.com/2/users/{user_id}/following");
-        let resp = self
-            .post_json_raw(&url, &serde_json::json!({ "target_user_id": target_user_id }))
-            .await?;
+        let request = Self::follow_request(user_id, target_user_id);
+        let resp = self.post_json_raw(&request.url, &request.body).await?;
         let r: serde_json::Value = resp
             .json()
             .await
@@ -1046,12 +1743,9 @@ impl XClient {
         conversation_id: &str,
         text: &str,
     ) -> Result<SendDmResult, String> {
-        let url = format!(
-            "https://api.x.com/2/dm_conversations/{conversation_id}/messages"
-        );
-        let body = serde_json::json!({ "text": text });
+        let request = Self::send_dm_request(conversation_id, text);
 
-        let resp = self.post_json_raw(&url, &body).await?;
+        let resp = self.post_json_raw(&request.url, &request.body).await?;
         let response: SendDmResponse = resp
             .json()
             .await
@@ -1087,6 +1781,133 @@ impl XClient {
         Ok(Self::map_tweet_list(response))
     }
 
+    /// Collect every recent tweet in a conversation via `conversation_id:` search,
+    /// following pagination. `filter` is appended to the query (e.g. `from:user`).
+    pub async fn search_conversation(
+        &self,
+        conversation_id: &str,
+        filter: Option<&str>,
+    ) -> Result<ConversationResult, String> {
+        let query = match filter {
+            Some(f) => format!("conversation_id:{conversation_id} {f}"),
+            None => format!("conversation_id:{conversation_id}"),
+        };
+
+        let mut tweets = Vec::new();
+        let mut next_token: Option<String> = None;
+        for page in 1..=MAX_CONVERSATION_PAGES {
+            let result = self
+                .search_recent_tweets(&query, 100, Some("recency"), next_token.as_deref())
+                .await?;
+            tracing::info!(
+                "search_conversation: page {page} returned {} tweets",
+                result.tweets.len()
+            );
+            tweets.extend(result.tweets);
+
+            match result.next_token {
+                Some(token) => next_token = Some(token),
+                None => {
+                    return Ok(ConversationResult {
+                        tweets,
+                        truncated: false,
+                    });
+                }
+            }
+
+            // Small delay to be respectful of rate limits
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        Ok(ConversationResult {
+            tweets,
+            truncated: true,
+        })
+    }
+
+    // --- Tweet lookup ---
+
+    /// Look up to 100 tweets by ID with author, media, referenced tweets and entities expanded.
+    pub async fn get_tweets(&self, ids: &[&str]) -> Result<TweetLookupResult, String> {
+        let mut params = tweet_field_params();
+        params.insert("ids".to_string(), ids.join(","));
+
+        let response: TweetLookupResponse = self.get_json(TWEETS_URL, &params).await?;
+
+        let errors = response
+            .errors
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| {
+                format!(
+                    "{}: {}",
+                    e.value.unwrap_or_else(|| "?".into()),
+                    e.detail.or(e.title).unwrap_or_else(|| "unknown error".into())
+                )
+            })
+            .collect();
+
+        Ok(TweetLookupResult {
+            tweets: Self::map_tweets(response.data, response.includes),
+            errors,
+        })
+    }
+
+    pub async fn get_tweet(&self, id: &str) -> Result<SearchTweetResult, String> {
+        let mut result = self.get_tweets(&[id]).await?;
+        if result.tweets.is_empty() {
+            let reason = result
+                .errors
+                .pop()
+                .unwrap_or_else(|| format!("{id}: not found"));
+            return Err(format!("Tweet lookup failed: {reason}"));
+        }
+        Ok(result.tweets.swap_remove(0))
+    }
+
+    /// Current metrics for up to 100 tweets. With `private`, also asks for
+    /// `non_public_metrics` and `organic_metrics`, which X only returns for the
+    /// authenticated user's own tweets from the last 30 days.
+    pub async fn get_tweet_metrics(
+        &self,
+        ids: &[&str],
+        private: bool,
+    ) -> Result<TweetMetricsResult, String> {
+        let mut fields = "public_metrics".to_string();
+        if private {
+            fields.push_str(",non_public_metrics,organic_metrics");
+        }
+        let mut params = BTreeMap::new();
+        params.insert("ids".to_string(), ids.join(","));
+        params.insert("tweet.fields".to_string(), fields);
+
+        let response: MetricsLookupResponse = self.get_json(TWEETS_URL, &params).await?;
+
+        let errors = response
+            .errors
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| {
+                (
+                    e.value.unwrap_or_default(),
+                    e.detail.or(e.title).unwrap_or_else(|| "unknown error".into()),
+                )
+            })
+            .collect();
+        let tweets = response
+            .data
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| TweetMetrics {
+                id: t.id,
+                public: t.public_metrics.unwrap_or_default(),
+                non_public: t.non_public_metrics,
+                organic: t.organic_metrics,
+            })
+            .collect();
+        Ok(TweetMetricsResult { tweets, errors })
+    }
+
     // --- Simple upload (images <=5MB) ---
 
     async fn simple_upload(&self, file_path: &Path, mime: &str) -> Result<String, String> {
@@ -1343,18 +2164,24 @@ impl XClient {
 
     // --- Helpers ---
 
+    /// Text exactly as it will be posted: normalization, then link rules.
+    pub fn prepare_text(&self, text: &str) -> NormalizedText {
+        let mut prepared = text::normalize(text, &self.config.normalize);
+        let (rewritten, applied) = links::apply(&prepared.text, &self.config.links);
+        prepared.text = rewritten;
+        prepared.applied.extend(applied);
+        prepared
+    }
+
     fn validate_tweet_text(&self, text: &str) -> Result<(), String> {
-        if text.trim().is_empty() {
-            return Err("Tweet text cannot be empty".into());
-        }
-        Ok(())
+        text::validate_tweet_text(text, self.config.max_tweet_length)
     }
 
     fn rate_limit_reset(&self, resp: &reqwest::Response) -> String {
-        if let Some(reset) = resp.headers().get("x-rate-limit-reset") {
-            if let Ok(val) = reset.to_str() {
-                return format!("Rate limit resets at timestamp {val}. ");
-            }
+        if let Some(reset) = resp.headers().get("x-rate-limit-reset")
+            && let Ok(val) = reset.to_str()
+        {
+            return format!("Rate limit resets at timestamp {val}. ");
         }
         String::new()
     }
@@ -1475,15 +2302,32 @@ fn pct_encode(input: &str) -> String {
     utf8_percent_encode(input, RFC3986).to_string()
 }
 
-fn tweet_list_params(max_results: u32) -> BTreeMap<String, String> {
+/// Tweet fields and expansions shared by every endpoint that returns tweets.
+fn tweet_field_params() -> BTreeMap<String, String> {
     let mut params = BTreeMap::new();
-    params.insert("max_results".to_string(), max_results.to_string());
     params.insert(
         "tweet.fields".to_string(),
-        "id,text,author_id,created_at,public_metrics".to_string(),
+        "id,text,author_id,created_at,conversation_id,public_metrics,\
+         referenced_tweets,attachments,entities"
+            .to_string(),
+    );
+    params.insert(
+        "expansions".to_string(),
+        "author_id,attachments.media_keys,referenced_tweets.id,\
+         referenced_tweets.id.author_id,entities.mentions.username"
+            .to_string(),
+    );
+    params.insert("user.fields".to_string(), "username,name".to_string());
+    params.insert(
+        "media.fields".to_string(),
+        "media_key,type,url,preview_image_url,alt_text".to_string(),
     );
-    params.insert("expansions".to_string(), "author_id".to_string());
-    params.insert("user.fields".to_string(), "username".to_string());
+    params
+}
+
+fn tweet_list_params(max_results: u32) -> BTreeMap<String, String> {
+    let mut params = tweet_field_params();
+    params.insert("max_results".to_string(), max_results.to_string());
     params
 }
 
@@ -1526,6 +2370,35 @@ fn media_info_from_path(path: &Path) -> Result<MediaInfo, String> {
     }
 }
 
+/// Pre-flight checks for a tweet's attachments: files exist, formats and sizes
+/// are supported, alt text is allowed, and the combination is valid.
+fn validate_attachments(media: &[MediaAttachment]) -> Result<(), String> {
+    let mut infos = Vec::new();
+    for attachment in media {
+        let path = Path::new(&attachment.path);
+        if !path.exists() {
+            return Err(format!("File not found: {}", attachment.path));
+        }
+        let info = media_info_from_path(path)?;
+        let file_size = std::fs::metadata(path)
+            .map_err(|e| format!("Cannot read file metadata: {e}"))?
+            .len();
+        if file_size > info.max_size {
+            return Err(format!(
+                "File too large: {} ({} bytes, max {}MB)",
+                attachment.path,
+                file_size,
+                info.max_size / (1024 * 1024)
+            ));
+        }
+        if attachment.alt_text.is_some() && info.media_type == MediaType::Video {
+            return Err("Alt text is not supported for videos".into());
+        }
+        infos.push(info);
+    }
+    validate_media_combination(&infos)
+}
+
 fn validate_media_combination(infos: &[MediaInfo]) -> Result<(), String> {
     if infos.len() <= 1 {
         return Ok(());
@@ -1561,3 +2434,55 @@ mod hex {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> XClient {
+        let config: AccountConfig = toml::from_str(
+            "api_key = \"k\"\napi_key_secret = \"s\"\n\
+             access_token = \"t\"\naccess_token_secret = \"ts\"",
+        )
+        .unwrap();
+        XClient::new(config, Client::new())
+    }
+
+    fn thread(texts: &[&str]) -> Vec<(String, Vec<MediaAttachment>)> {
+        texts.iter().map(|t| (t.to_string(), Vec::new())).collect()
+    }
+
+    // Pre-flight failures return before any request is sent.
+
+    #[tokio::test]
+    async fn preflight_failure_on_a_new_thread_posts_nothing() {
+        let tweets = thread(&["one", "", "three"]);
+        let result = client()
+            .post_thread_from(&tweets, 0, None, "me", |_, _| {})
+            .await;
+        assert!(result.posted.is_empty());
+        assert_eq!(result.failed_index, None);
+        let error = result.error.unwrap();
+        assert!(
+            error.starts_with("Tweet 2 of 3 failed pre-flight validation (nothing was posted)"),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_failure_on_resume_mentions_live_tweets() {
+        let tweets = thread(&["one", "two", "", "four"]);
+        let result = client()
+            .post_thread_from(&tweets, 2, Some("222"), "me", |_, _| {})
+            .await;
+        assert!(result.posted.is_empty());
+        let error = result.error.unwrap();
+        assert!(
+            error.starts_with(
+                "Tweet 3 of 4 failed pre-flight validation \
+                 (nothing new was posted; tweets 1-2 are already live)"
+            ),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Tools that change something on X (or queue a change). Read-only tools and
+/// local draft edits are not audited.
+const MUTATING_TOOLS: &[&str] = &[
+    "post_tweet",
+    "post_to_accounts",
+    "post_from_template",
+    "post_thread",
+    "post_thread_from_file",
+    "resume_thread",
+    "edit_tweet",
+    "delete_tweet",
+    "publish_draft",
+    "schedule_tweet",
+    "schedule_thread",
+    "cancel_scheduled",
+    "upload_media",
+    "follow_user",
+    "unfollow_user",
+    "like_tweet",
+    "unlike_tweet",
+    "retweet",
+    "unretweet",
+    "send_dm",
+];
+
+/// Argument names whose values are never written to the log.
+const SECRET_KEYS: &[&str] = &["token", "secret", "password", "api_key", "authorization"];
+
+/// Longest result text kept per entry.
+const MAX_RESULT_BYTES: usize = 4000;
+
+// --- Config ---
+
+/// `[audit]` config section. The audit log is off unless the section exists.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Log file (default `<data_dir>/audit/audit.jsonl`).
+    pub path: Option<PathBuf>,
+    /// Rotate once the file reaches this size.
+    pub max_size_mb: u64,
+    /// Also rotate when the UTC date changes.
+    pub rotate_daily: bool,
+    /// Rotated files to keep; older ones are deleted. Unset keeps all.
+    pub max_files: Option<usize>,
+    /// Log a SHA-256 of DM text instead of the text itself.
+    pub hash_dm_text: bool,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_size_mb: 10,
+            rotate_daily: false,
+            max_files: None,
+            hash_dm_text: false,
+        }
+    }
+}
+
+impl AuditConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_size_mb == 0 {
+            return Err("max_size_mb must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+// --- Log ---
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    started_at: String,
+    finished_at: String,
+    duration_ms: i64,
+    tool: &'a str,
+    account: Option<&'a str>,
+    arguments: Value,
+    is_error: bool,
+    result: String,
+}
+
+/// Append-only JSONL log of mutating tool calls.
+pub struct AuditLog {
+    path: PathBuf,
+    config: AuditConfig,
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(config: AuditConfig, data_dir: &Path) -> Self {
+        let path = config
+            .path
+            .clone()
+            .unwrap_or_else(|| data_dir.join("audit").join("audit.jsonl"));
+        Self {
+            path,
+            config,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn is_audited(tool: &str) -> bool {
+        MUTATING_TOOLS.contains(&tool)
+    }
+
+    /// Write one entry. Failures are logged; they never fail the call itself.
+    pub fn record(
+        &self,
+        tool: &str,
+        account: Option<&str>,
+        mut arguments: Value,
+        started_at: DateTime<Utc>,
+        is_error: bool,
+        result: &str,
+    ) {
+        redact_secrets(&mut arguments);
+        let finished_at = Utc::now();
+        let mut result = result.to_string();
+        if tool == "send_dm"
+            && self.config.hash_dm_text
+            && let Some(text) = arguments.get_mut("text")
+            && let Some(raw) = text.as_str().map(str::to_string)
+        {
+            let digest = Sha256::digest(raw.as_bytes());
+            let hashed: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+            let hashed = format!("sha256:{hashed}");
+            // Dry runs echo the request body, so scrub the result as well.
+            let escaped = serde_json::to_string(&raw).unwrap_or_default();
+            let escaped = escaped.trim_matches('"');
+            if !raw.trim().is_empty() {
+                result = result.replace(&raw, &hashed).replace(escaped, &hashed);
+            }
+            *text = Value::String(hashed);
+        }
+        if result.len() > MAX_RESULT_BYTES {
+            let mut end = MAX_RESULT_BYTES;
+            while !result.is_char_boundary(end) {
+                end -= 1;
+            }
+            result.truncate(end);
+            result.push_str("… (truncated)");
+        }
+        let entry = AuditEntry {
+            started_at: started_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            finished_at: finished_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            duration_ms: (finished_at - started_at).num_milliseconds(),
+            tool,
+            account,
+            arguments,
+            is_error,
+            result,
+        };
+        if let Err(e) = self.append(&entry) {
+            tracing::warn!("Failed to write audit log {}: {e}", self.path.display());
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<(), String> {
+        let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        // The MCP server and the daemon may share a log; serialize rotation
+        // and writes across processes too.
+        let lock_file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))
+            .map_err(|e| e.to_string())?;
+        lock_file.lock().map_err(|e| e.to_string())?;
+
+        self.rotate_if_needed()?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| e.to_string())
+    }
+
+    fn rotate_if_needed(&self) -> Result<(), String> {
+        let metadata = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let too_big = metadata.len() >= self.config.max_size_mb * 1024 * 1024;
+        let modified: Option<DateTime<Utc>> = metadata.modified().ok().map(Into::into);
+        let new_day = self.config.rotate_daily
+            && modified.is_some_and(|m| m.date_naive() != Utc::now().date_naive());
+        if !(too_big || new_day) || metadata.len() == 0 {
+            return Ok(());
+        }
+
+        let stem = self.file_stem();
+        let stamp = modified.unwrap_or_else(Utc::now).format("%Y%m%d-%H%M%S");
+        // Two rotations within a second share a stamp; number the later ones.
+        let mut rotated = self.path.with_file_name(format!("{stem}-{stamp}.jsonl"));
+        let mut counter = 1;
+        while rotated.exists() {
+            rotated = self
+                .path
+                .with_file_name(format!("{stem}-{stamp}-{counter}.jsonl"));
+            counter += 1;
+        }
+        std::fs::rename(&self.path, &rotated)
+            .map_err(|e| format!("Failed to rotate to {}: {e}", rotated.display()))?;
+        tracing::info!("Rotated audit log to {}", rotated.display());
+
+        if let Some(keep) = self.config.max_files {
+            self.prune(&stem, keep);
+        }
+        Ok(())
+    }
+
+    fn file_stem(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "audit".to_string())
+    }
+
+    /// Delete the oldest rotated files beyond `keep`, ordered by the stamp and
+    /// counter in their names.
+    fn prune(&self, stem: &str, keep: usize) {
+        let Some(dir) = self.path.parent() else {
+            return;
+        };
+        let prefix = format!("{stem}-");
+        let mut rotated: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".jsonl"))
+            })
+            .collect();
+        rotated.sort_by_cached_key(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            rotation_order(&name[prefix.len()..])
+        });
+        let excess = rotated.len().saturating_sub(keep);
+        for path in rotated.into_iter().take(excess) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                tracing::warn!("Failed to delete old audit log {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+/// Sort key for a rotated file name after the `{stem}-` prefix, e.g.
+/// `20250601-093000-2.jsonl`: the stamp, then the counter (none sorts first).
+fn rotation_order(name: &str) -> (String, u32) {
+    let name = name.trim_end_matches(".jsonl");
+    match name.rsplit_once('-') {
+        // The stamp itself contains one '-', so only a third part is a counter.
+        Some((stamp, counter)) if stamp.contains('-') => {
+            (stamp.to_string(), counter.parse().unwrap_or(0))
+        }
+        _ => (name.to_string(), 0),
+    }
+}
+
+/// Replace the values of secret-looking keys, at any depth, with a marker.
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEYS.iter().any(|s| key.contains(s)) {
+                    *value = Value::String("[REDACTED]".to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated_names_sort_by_stamp_then_counter() {
+        let mut names = vec![
+            "20250601-093000-2.jsonl",
+            "20250601-093000-10.jsonl",
+            "20250601-093000.jsonl",
+            "20250531-235959-1.jsonl",
+        ];
+        names.sort_by_key(|n| rotation_order(n));
+        assert_eq!(
+            names,
+            [
+                "20250531-235959-1.jsonl",
+                "20250601-093000.jsonl",
+                "20250601-093000-2.jsonl",
+                "20250601-093000-10.jsonl",
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_max_size_is_rejected() {
+        let config = AuditConfig {
+            max_size_mb: 0,
+            ..AuditConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(AuditConfig::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::api::MediaAttachment;
+use crate::store::{JsonStore, now_unix};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// One post, identified by a fingerprint of its content. A pending entry is a
+/// post in flight: it has no tweet ID yet, and blocks the same content until
+/// it is confirmed or released.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PostFingerprint {
+    pub hash: String,
+    pub tweet_id: String,
+    pub url: String,
+    pub posted_at: u64,
+    #[serde(default)]
+    pub pending: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PostLog {
+    posts: Vec<PostFingerprint>,
+}
+
+/// Per-account record of recently posted content, used to refuse accidental
+/// double posts (e.g. an agent retrying after a timeout).
+pub struct DuplicateGuard {
+    store: JsonStore<PostLog>,
+    lock: Mutex<()>,
+}
+
+impl DuplicateGuard {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            store: JsonStore::new(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Hash of the tweet text, the contents of every media file, any
+    /// pre-uploaded media IDs and the reply and quote targets. Replying with the
+    /// same text to different tweets is not a duplicate.
+    pub fn fingerprint(
+        text: &str,
+        media: &[MediaAttachment],
+        media_ids: Option<&[String]>,
+        reply_to: Option<&str>,
+        quote: Option<&str>,
+    ) -> Result<String, String> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"text:");
+        hasher.update(text.trim().as_bytes());
+        for attachment in media {
+            hasher.update(b"\0media:");
+            hasher.update(hash_file(&attachment.path)?.as_bytes());
+        }
+        for id in media_ids.unwrap_or_default() {
+            hasher.update(b"\0media_id:");
+            hasher.update(id.as_bytes());
+        }
+        if let Some(reply_to) = reply_to {
+            hasher.update(b"\0reply_to:");
+            hasher.update(reply_to.as_bytes());
+        }
+        if let Some(quote) = quote {
+            hasher.update(b"\0quote:");
+            hasher.update(quote.as_bytes());
+        }
+        Ok(hex(&hasher.finalize()))
+    }
+
+    /// The most recent post with this fingerprint within the window, if any.
+    pub fn find_recent(
+        &self,
+        account: &str,
+        hash: &str,
+        window_secs: u64,
+    ) -> Result<Option<PostFingerprint>, String> {
+        let log = self.store.get(&store_id(account))?.unwrap_or_default();
+        Ok(Self::latest(&log, hash, window_secs).cloned())
+    }
+
+    /// Like `find_recent`, but if there is no match, reserve the fingerprint
+    /// with a pending entry in the same locked step, so a concurrent identical
+    /// post is refused. Follow up with `record` on success or `release` on failure.
+    pub fn reserve(
+        &self,
+        account: &str,
+        hash: &str,
+        window_secs: u64,
+    ) -> Result<Option<PostFingerprint>, String> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let id = store_id(account);
+        let mut log = self.store.get(&id)?.unwrap_or_default();
+        if let Some(previous) = Self::latest(&log, hash, window_secs) {
+            return Ok(Some(previous.clone()));
+        }
+        let now = now_unix();
+        log.posts
+            .retain(|p| p.posted_at >= now.saturating_sub(window_secs));
+        log.posts.push(PostFingerprint {
+            hash: hash.to_string(),
+            tweet_id: String::new(),
+            url: String::new(),
+            posted_at: now,
+            pending: true,
+        });
+        self.store.save(&id, &log)?;
+        Ok(None)
+    }
+
+    /// Drop the pending entry for a post that failed.
+    pub fn release(&self, account: &str, hash: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let id = store_id(account);
+        let mut log = self.store.get(&id)?.unwrap_or_default();
+        log.posts.retain(|p| !(p.pending && p.hash == hash));
+        self.store.save(&id, &log)
+    }
+
+    /// Record a successful post, replacing its pending entry if there is one,
+    /// and drop entries older than the window.
+    pub fn record(
+        &self,
+        account: &str,
+        hash: String,
+        tweet_id: &str,
+        url: &str,
+        window_secs: u64,
+    ) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let id = store_id(account);
+        let now = now_unix();
+        let cutoff = now.saturating_sub(window_secs);
+        let mut log = self.store.get(&id)?.unwrap_or_default();
+        log.posts
+            .retain(|p| p.posted_at >= cutoff && !(p.pending && p.hash == hash));
+        log.posts.push(PostFingerprint {
+            hash,
+            tweet_id: tweet_id.to_string(),
+            url: url.to_string(),
+            posted_at: now,
+            pending: false,
+        });
+        self.store.save(&id, &log)
+    }
+
+    fn latest<'a>(log: &'a PostLog, hash: &str, window_secs: u64) -> Option<&'a PostFingerprint> {
+        let cutoff = now_unix().saturating_sub(window_secs);
+        log.posts
+            .iter()
+            .filter(|p| p.hash == hash && p.posted_at >= cutoff)
+            .max_by_key(|p| p.posted_at)
+    }
+}
+
+/// Account names are free-form in the config; those that aren't already a
+/// valid store ID are keyed by their hash instead.
+fn store_id(account: &str) -> String {
+    let plain = !account.is_empty()
+        && account
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if plain {
+        account.to_string()
+    } else {
+        format!("account-{}", hex(&Sha256::digest(account.as_bytes())))
+    }
+}
+
+fn hash_file(path: &str) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {path}: {e}"))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {path}: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::new_id;
+
+    const HOUR: u64 = 3_600;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        std::env::temp_dir().join(new_id(&format!("post-x-dedupe-{label}")))
+    }
+
+    fn media(path: &std::path::Path) -> MediaAttachment {
+        MediaAttachment {
+            path: path.to_string_lossy().into_owned(),
+            alt_text: None,
+        }
+    }
+
+    fn hash(text: &str, reply_to: Option<&str>, quote: Option<&str>) -> String {
+        DuplicateGuard::fingerprint(text, &[], None, reply_to, quote).unwrap()
+    }
+
+    #[test]
+    fn fingerprint_ignores_surrounding_whitespace_and_media_paths() {
+        let dir = temp_dir("media");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (a, b, c) = (dir.join("a.png"), dir.join("b.png"), dir.join("c.png"));
+        std::fs::write(&a, b"same bytes").unwrap();
+        std::fs::write(&b, b"same bytes").unwrap();
+        std::fs::write(&c, b"other bytes").unwrap();
+        let with = |path: &std::path::Path| {
+            DuplicateGuard::fingerprint("Hi", &[media(path)], None, None, None).unwrap()
+        };
+        let (hash_a, hash_b, hash_c) = (with(&a), with(&b), with(&c));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(hash("Hi", None, None), hash("  Hi\n", None, None));
+        assert_eq!(hash_a, hash_b);
+        assert_ne!(hash_a, hash_c);
+        assert_ne!(hash_a, hash("Hi", None, None));
+    }
+
+    #[test]
+    fn reply_and_quote_targets_are_part_of_the_fingerprint() {
+        let plain = hash("Agreed", None, None);
+        assert_ne!(plain, hash("Agreed", Some("1"), None));
+        assert_ne!(
+            hash("Agreed", Some("1"), None),
+            hash("Agreed", Some("2"), None)
+        );
+        assert_ne!(plain, hash("Agreed", None, Some("1")));
+        assert_ne!(
+            hash("Agreed", Some("1"), None),
+            hash("Agreed", None, Some("1"))
+        );
+    }
+
+    #[test]
+    fn recorded_post_matches_inside_the_window_only() {
+        let dir = temp_dir("window");
+        let guard = DuplicateGuard::new(dir.clone());
+        let h = hash("Hello", None, None);
+        guard
+            .record(
+                "main",
+                h.clone(),
+                "111",
+                "https://x.com/me/status/111",
+                24 * HOUR,
+            )
+            .unwrap();
+        let inside = guard.find_recent("main", &h, 24 * HOUR).unwrap();
+        let other_account = guard.find_recent("alt", &h, 24 * HOUR).unwrap();
+        let other_text = guard
+            .find_recent("main", &hash("Hi", None, None), 24 * HOUR)
+            .unwrap();
+
+        // Age the entry past a one-hour window.
+        let mut log = guard.store.load("main").unwrap();
+        log.posts[0].posted_at -= 2 * HOUR;
+        guard.store.save("main", &log).unwrap();
+        let outside = guard.find_recent("main", &h, HOUR).unwrap();
+        let still_inside = guard.find_recent("main", &h, 24 * HOUR).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(inside.map(|p| p.tweet_id), Some("111".to_string()));
+        assert!(other_account.is_none());
+        assert!(other_text.is_none());
+        assert!(outside.is_none());
+        assert!(still_inside.is_some());
+    }
+
+    #[test]
+    fn reservation_blocks_until_released_or_recorded() {
+        let dir = temp_dir("reserve");
+        let guard = DuplicateGuard::new(dir.clone());
+        let h = hash("Launch day", None, None);
+        let first = guard.reserve("main", &h, HOUR).unwrap();
+        let second = guard.reserve("main", &h, HOUR).unwrap();
+        guard.release("main", &h).unwrap();
+        let after_release = guard.reserve("main", &h, HOUR).unwrap();
+        guard
+            .record(
+                "main",
+                h.clone(),
+                "222",
+                "https://x.com/me/status/222",
+                HOUR,
+            )
+            .unwrap();
+        let after_record = guard.reserve("main", &h, HOUR).unwrap();
+        let log = guard.store.load("main").unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(first.is_none());
+        assert!(second.is_some_and(|p| p.pending));
+        assert!(after_release.is_none());
+        assert!(after_record.is_some_and(|p| !p.pending && p.tweet_id == "222"));
+        // The confirmed post replaced its reservation.
+        assert_eq!(log.posts.len(), 1);
+    }
+
+    #[test]
+    fn unsafe_account_names_are_hashed_into_the_store_id() {
+        assert_eq!(store_id("main_2"), "main_2");
+        let hashed = store_id("Brand Account/../x");
+        assert!(hashed.starts_with("account-"));
+        assert_eq!(hashed.len(), "account-".len() + 64);
+        assert_ne!(hashed, store_id("Brand Account"));
+
+        let dir = temp_dir("names");
+        let guard = DuplicateGuard::new(dir.clone());
+        let h = hash("Hi", None, None);
+        let reserved = guard.reserve("Brand Account", &h, HOUR);
+        let found = guard.find_recent("Brand Account", &h, HOUR);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(reserved.unwrap().is_none());
+        assert!(found.unwrap().is_some());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::api::TweetMetrics;
+use serde::Deserialize;
+
+// --- Config ---
+
+/// `[engagement]` config section. Metrics are only tracked in the background
+/// when the section exists.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EngagementConfig {
+    /// How often to re-fetch metrics.
+    pub interval_minutes: u64,
+    /// Track tweets for this many days after they were posted.
+    pub track_days: u64,
+    /// Also fetch `non_public_metrics` and `organic_metrics`.
+    pub private_metrics: bool,
+}
+
+impl Default for EngagementConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 60,
+            track_days: 7,
+            private_metrics: true,
+        }
+    }
+}
+
+impl EngagementConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_minutes < 5 {
+            return Err("interval_minutes must be at least 5".to_string());
+        }
+        if self.track_days == 0 {
+            return Err("track_days must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Identifies the interval, so saved state is reset when the config changes.
+    pub fn schedule_key(&self) -> String {
+        format!("every {}m", self.interval_minutes)
+    }
+}
+
+// --- Samples ---
+
+/// One tweet's metrics at one point in time. Private counts are `None` when
+/// X didn't return them.
+#[derive(Debug, Clone)]
+pub struct MetricsSample {
+    pub tweet_id: String,
+    pub account: String,
+    pub fetched_at: u64,
+    pub impressions: Option<u64>,
+    pub likes: u64,
+    pub retweets: u64,
+    pub replies: u64,
+    pub quotes: u64,
+    pub bookmarks: Option<u64>,
+    pub url_clicks: Option<u64>,
+    pub profile_clicks: Option<u64>,
+    pub organic_impressions: Option<u64>,
+    pub organic_likes: Option<u64>,
+    pub organic_retweets: Option<u64>,
+    pub organic_replies: Option<u64>,
+}
+
+impl MetricsSample {
+    pub fn from_api(account: &str, tweet: &TweetMetrics, fetched_at: u64) -> Self {
+        let public = &tweet.public;
+        let non_public = tweet.non_public.as_ref();
+        let organic = tweet.organic.as_ref();
+        Self {
+            tweet_id: tweet.id.clone(),
+            account: account.to_string(),
+            fetched_at,
+            impressions: public.impression_count,
+            likes: public.like_count.unwrap_or(0),
+            retweets: public.retweet_count.unwrap_or(0),
+            replies: public.reply_count.unwrap_or(0),
+            quotes: public.quote_count.unwrap_or(0),
+            bookmarks: public.bookmark_count,
+            url_clicks: non_public.and_then(|m| m.url_link_clicks),
+            profile_clicks: non_public.and_then(|m| m.user_profile_clicks),
+            organic_impressions: organic.and_then(|m| m.impression_count),
+            organic_likes: organic.and_then(|m| m.like_count),
+            organic_retweets: organic.and_then(|m| m.retweet_count),
+            organic_replies: organic.and_then(|m| m.reply_count),
+        }
+    }
+
+    /// Likes, retweets, replies, quotes and bookmarks. Public counts only, so
+    /// rates stay comparable between tweets with and without private metrics.
+    pub fn engagements(&self) -> u64 {
+        self.likes + self.retweets + self.replies + self.quotes + self.bookmarks.unwrap_or(0)
+    }
+
+    /// Engagements per impression, if impressions are known and non-zero.
+    pub fn engagement_rate(&self) -> Option<f64> {
+        self.impressions
+            .filter(|&i| i > 0)
+            .map(|i| self.engagements() as f64 / i as f64)
+    }
+}
+
+/// Seconds after posting at which growth curves are sampled.
+const CURVE_POINTS: &[u64] = &[3_600, 6 * 3_600, 86_400, 3 * 86_400, 7 * 86_400];
+
+/// Samples that best show how a tweet grew: the one nearest each of
+/// `CURVE_POINTS` and the latest one. `samples` must be oldest first.
+pub fn growth_curve(samples: &[MetricsSample], posted_at: u64) -> Vec<&MetricsSample> {
+    let Some(last) = samples.last() else {
+        return Vec::new();
+    };
+    let mut curve: Vec<&MetricsSample> = Vec::new();
+    for &offset in CURVE_POINTS {
+        let target = posted_at + offset;
+        if target > last.fetched_at {
+            break;
+        }
+        let nearest = samples
+            .iter()
+            .min_by_key(|s| s.fetched_at.abs_diff(target))
+            .expect("samples is not empty");
+        if curve
+            .last()
+            .is_none_or(|prev| prev.fetched_at != nearest.fetched_at)
+        {
+            curve.push(nearest);
+        }
+    }
+    if curve
+        .last()
+        .is_none_or(|prev| prev.fetched_at != last.fetched_at)
+    {
+        curve.push(last);
+    }
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoryEntry, PostHistory};
+    use crate::params::HistoryAction;
+    use crate::store::new_id;
+
+    const HOUR: u64 = 3_600;
+    const DAY: u64 = 86_400;
+    const POSTED_AT: u64 = 1_750_000_000;
+
+    fn sample(tweet_id: &str, age: u64) -> MetricsSample {
+        MetricsSample {
+            tweet_id: tweet_id.to_string(),
+            account: "main".to_string(),
+            fetched_at: POSTED_AT + age,
+            impressions: None,
+            likes: 0,
+            retweets: 0,
+            replies: 0,
+            quotes: 0,
+            bookmarks: None,
+            url_clicks: None,
+            profile_clicks: None,
+            organic_impressions: None,
+            organic_likes: None,
+            organic_retweets: None,
+            organic_replies: None,
+        }
+    }
+
+    fn ages(curve: &[&MetricsSample]) -> Vec<u64> {
+        curve.iter().map(|s| s.fetched_at - POSTED_AT).collect()
+    }
+
+    #[test]
+    fn growth_curve_of_no_samples_is_empty() {
+        assert!(growth_curve(&[], POSTED_AT).is_empty());
+    }
+
+    #[test]
+    fn growth_curve_before_the_first_point_is_the_latest_sample() {
+        let samples = [sample("1", 60), sample("1", 30 * 60)];
+        assert_eq!(ages(&growth_curve(&samples, POSTED_AT)), [30 * 60]);
+    }
+
+    #[test]
+    fn growth_curve_lists_a_sample_nearest_several_points_once() {
+        // 50 minutes is nearest to 1h, 6h and 1d; 3.5 days is nearest to 3d
+        // and also the latest.
+        let samples = [sample("1", 50 * 60), sample("1", 3 * DAY + 12 * HOUR)];
+        assert_eq!(
+            ages(&growth_curve(&samples, POSTED_AT)),
+            [50 * 60, 3 * DAY + 12 * HOUR]
+        );
+    }
+
+    #[test]
+    fn growth_curve_keeps_every_point_and_the_latest_sample() {
+        let samples: Vec<_> = [HOUR, 2 * HOUR, 6 * HOUR, DAY, 3 * DAY, 7 * DAY, 10 * DAY]
+            .into_iter()
+            .map(|age| sample("1", age))
+            .collect();
+        assert_eq!(
+            ages(&growth_curve(&samples, POSTED_AT)),
+            [HOUR, 6 * HOUR, DAY, 3 * DAY, 7 * DAY, 10 * DAY]
+        );
+    }
+
+    #[test]
+    fn engagement_rate_needs_impressions() {
+        let mut s = sample("1", HOUR);
+        s.likes = 6;
+        s.retweets = 2;
+        s.bookmarks = Some(2);
+        assert_eq!(s.engagements(), 10);
+        assert_eq!(s.engagement_rate(), None);
+        s.impressions = Some(0);
+        assert_eq!(s.engagement_rate(), None);
+        s.impressions = Some(200);
+        assert_eq!(s.engagement_rate(), Some(0.05));
+    }
+
+    #[test]
+    fn metrics_round_trip_through_history() {
+        let dir = std::env::temp_dir().join(new_id("post-x-engagement"));
+        let history = PostHistory::new(dir.join("history.db"));
+        for (action, tweet_id) in [
+            (HistoryAction::Tweet, "1"),
+            (HistoryAction::Tweet, "2"),
+            (HistoryAction::Delete, "2"),
+        ] {
+            let mut entry = HistoryEntry::new(action, "main", tweet_id);
+            entry.created_at = POSTED_AT;
+            history.record(&entry).unwrap();
+        }
+        let mut later = sample("1", DAY);
+        later.impressions = Some(500);
+        later.likes = 12;
+        later.bookmarks = Some(3);
+        later.url_clicks = Some(4);
+        later.organic_likes = Some(11);
+        let samples = [later, sample("1", HOUR), sample("2", HOUR)];
+        history.record_metrics(&samples).unwrap();
+
+        let all = history.metrics_since(None, POSTED_AT).unwrap();
+        let other_account = history.metrics_since(Some("alt"), POSTED_AT).unwrap();
+        let too_late = history.metrics_since(None, POSTED_AT + 1).unwrap();
+        drop(history);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // The deleted tweet is left out and samples come back oldest first.
+        assert_eq!(ages(&all.iter().collect::<Vec<_>>()), [HOUR, DAY]);
+        assert!(all.iter().all(|s| s.tweet_id == "1" && s.account == "main"));
+        let (first, latest) = (&all[0], &all[1]);
+        assert_eq!(first.impressions, None);
+        assert_eq!(first.bookmarks, None);
+        assert_eq!(latest.impressions, Some(500));
+        assert_eq!(latest.likes, 12);
+        assert_eq!(latest.bookmarks, Some(3));
+        assert_eq!(latest.url_clicks, Some(4));
+        assert_eq!(latest.profile_clicks, None);
+        assert_eq!(latest.organic_likes, Some(11));
+        assert!(other_account.is_empty());
+        assert!(too_late.is_empty());
+    }
+}
@@ -0,0 +1,316 @@
+use crate::api::{SearchTweetResult, UserSummary};
+use crate::params::ExportFormat;
+use serde_json::{Map, Value, json};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// A record that can be written to an export file. `to_json` returns an
+/// object with one key per column; CSV flattens arrays to space-separated values.
+pub trait ExportRow {
+    const COLUMNS: &'static [&'static str];
+
+    fn to_json(&self) -> Map<String, Value>;
+}
+
+impl ExportRow for UserSummary {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "username",
+        "name",
+        "description",
+        "followers_count",
+        "following_count",
+        "tweet_count",
+    ];
+
+    fn to_json(&self) -> Map<String, Value> {
+        let metrics = self.public_metrics.as_ref();
+        let row = json!({
+            "id": self.id,
+            "username": self.username,
+            "name": self.name,
+            "description": self.description,
+            "followers_count": metrics.map(|m| m.followers_count),
+            "following_count": metrics.map(|m| m.following_count),
+            "tweet_count": metrics.map(|m| m.tweet_count),
+        });
+        into_object(row)
+    }
+}
+
+impl ExportRow for SearchTweetResult {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "url",
+        "created_at",
+        "author_id",
+        "username",
+        "author_name",
+        "text",
+        "conversation_id",
+        "like_count",
+        "retweet_count",
+        "reply_count",
+        "quote_count",
+        "referenced_tweets",
+        "hashtags",
+        "mentions",
+        "urls",
+        "media",
+    ];
+
+    fn to_json(&self) -> Map<String, Value> {
+        let url = self
+            .username
+            .as_ref()
+            .map(|u| format!("https://x.com/{u}/status/{}", self.id));
+        let referenced: Vec<String> = self
+            .referenced_tweets
+            .iter()
+            .map(|r| format!("{}:{}", r.kind, r.id))
+            .collect();
+        let urls: Vec<&str> = self.urls.iter().map(|u| u.expanded_url.as_str()).collect();
+        let media: Vec<&str> = self.media.iter().filter_map(|m| m.url.as_deref()).collect();
+        let row = json!({
+            "id": self.id,
+            "url": url,
+            "created_at": self.created_at,
+            "author_id": self.author_id,
+            "username": self.username,
+            "author_name": self.author_name,
+            "text": self.text,
+            "conversation_id": self.conversation_id,
+            "like_count": self.like_count,
+            "retweet_count": self.retweet_count,
+            "reply_count": self.reply_count,
+            "quote_count": self.quote_count,
+            "referenced_tweets": referenced,
+            "hashtags": self.hashtags,
+            "mentions": self.mentions,
+            "urls": urls,
+            "media": media,
+        });
+        into_object(row)
+    }
+}
+
+fn into_object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+impl ExportFormat {
+    /// The explicit format, or the one matching the file extension (JSONL
+    /// unless the path ends in `.csv`).
+    pub fn resolve(explicit: Option<Self>, path: &Path) -> Self {
+        explicit.unwrap_or_else(|| {
+            let is_csv = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+            if is_csv { Self::Csv } else { Self::Jsonl }
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Jsonl => "JSONL",
+        }
+    }
+}
+
+// --- Writer ---
+
+/// Writes rows to a temp file next to `path` as they arrive, and moves it into
+/// place on `finish`. A failed or abandoned export leaves any existing file alone,
+/// and an existing file is only replaced with `overwrite`.
+pub struct Exporter<T> {
+    path: PathBuf,
+    tmp: PathBuf,
+    format: ExportFormat,
+    overwrite: bool,
+    writer: BufWriter<File>,
+    rows: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: ExportRow> Exporter<T> {
+    pub fn create(
+        path: PathBuf,
+        format: Option<ExportFormat>,
+        overwrite: bool,
+    ) -> Result<Self, String> {
+        if !overwrite {
+            refuse_existing(&path)?;
+        }
+        let format = ExportFormat::resolve(format, &path);
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".part");
+        let tmp = PathBuf::from(tmp);
+        let file =
+            File::create(&tmp).map_err(|e| format!("Failed to create {}: {e}", tmp.display()))?;
+        let mut exporter = Self {
+            path,
+            tmp,
+            format,
+            overwrite,
+            writer: BufWriter::new(file),
+            rows: 0,
+            _marker: PhantomData,
+        };
+        if format == ExportFormat::Csv {
+            let header: Vec<String> = T::COLUMNS.iter().map(|c| c.to_string()).collect();
+            exporter.write_line(&csv_line(&header))?;
+        }
+        Ok(exporter)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    pub fn write(&mut self, row: &T) -> Result<(), String> {
+        let object = row.to_json();
+        let line = match self.format {
+            // Built by hand so keys keep the column order.
+            ExportFormat::Jsonl => {
+                let fields: Vec<String> = T::COLUMNS
+                    .iter()
+                    .map(|c| {
+                        let value = object.get(*c).unwrap_or(&Value::Null);
+                        format!("{}:{value}", Value::from(*c))
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+            ExportFormat::Csv => {
+                let fields: Vec<String> = T::COLUMNS
+                    .iter()
+                    .map(|c| csv_value(object.get(*c).unwrap_or(&Value::Null)))
+                    .collect();
+                csv_line(&fields)
+            }
+        };
+        self.write_line(&line)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn write_all(&mut self, rows: &[T]) -> Result<(), String> {
+        rows.iter().try_for_each(|row| self.write(row))
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        self.writer
+            .write_all(line.as_bytes())
+            .and_then(|()| self.writer.write_all(b"\n"))
+            .map_err(|e| format!("Failed to write {}: {e}", self.tmp.display()))
+    }
+
+    /// Flush and move the file into place. Returns the number of rows written.
+    pub fn finish(mut self) -> Result<usize, String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to write {}: {e}", self.tmp.display()))?;
+        // Checked again in case the file appeared while the export was running.
+        if !self.overwrite {
+            refuse_existing(&self.path)?;
+        }
+        std::fs::rename(&self.tmp, &self.path)
+            .map_err(|e| format!("Failed to move export to {}: {e}", self.path.display()))?;
+        Ok(self.rows)
+    }
+}
+
+impl<T> Drop for Exporter<T> {
+    fn drop(&mut self) {
+        // Gone already if `finish` renamed it.
+        let _ = std::fs::remove_file(&self.tmp);
+    }
+}
+
+fn refuse_existing(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!(
+            "{} already exists. Pass overwrite: true to replace it, or choose another path.",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+// --- CSV ---
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(csv_value).collect::<Vec<_>>().join(" "),
+        other => other.to_string(),
+    }
+}
+
+/// One RFC 4180 record: fields with commas, quotes or line breaks are quoted.
+fn csv_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::new_id;
+
+    fn user(id: &str, description: &str) -> UserSummary {
+        serde_json::from_value(json!({
+            "id": id,
+            "username": format!("user{id}"),
+            "name": "Name, with comma",
+            "description": description,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn existing_file_is_kept_without_overwrite() {
+        let dir = std::env::temp_dir().join(new_id("post-x-export"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("followers.csv");
+        std::fs::write(&path, "keep me").unwrap();
+
+        let refused = Exporter::<UserSummary>::create(path.clone(), None, false);
+        let kept = std::fs::read_to_string(&path).unwrap();
+
+        let mut exporter = Exporter::create(path.clone(), None, true).unwrap();
+        exporter.write(&user("1", "says \"hi\"")).unwrap();
+        let rows = exporter.finish().unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(refused.is_err());
+        assert_eq!(kept, "keep me");
+        assert_eq!(rows, 1);
+        assert_eq!(
+            written,
+            "id,username,name,description,followers_count,following_count,tweet_count\n\
+             1,user1,\"Name, with comma\",\"says \"\"hi\"\"\",,,\n"
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use crate::engagement::MetricsSample;
+use crate::params::HistoryAction;
+use crate::store::now_unix;
+use chrono::{DateTime, NaiveDate};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS posts (
+        id          INTEGER PRIMARY KEY,
+        action      TEXT NOT NULL,
+        account     TEXT NOT NULL,
+        tweet_id    TEXT NOT NULL,
+        url         TEXT,
+        text        TEXT,
+        media       TEXT NOT NULL DEFAULT '[]',
+        reply_to    TEXT,
+        quote_of    TEXT,
+        thread_id   TEXT,
+        created_at  INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS posts_account_created ON posts (account, created_at);
+    CREATE INDEX IF NOT EXISTS posts_tweet_id ON posts (tweet_id);
+    CREATE TABLE IF NOT EXISTS tweet_metrics (
+        id                  INTEGER PRIMARY KEY,
+        tweet_id            TEXT NOT NULL,
+        account             TEXT NOT NULL,
+        fetched_at          INTEGER NOT NULL,
+        impressions         INTEGER,
+        likes               INTEGER NOT NULL,
+        retweets            INTEGER NOT NULL,
+        replies             INTEGER NOT NULL,
+        quotes              INTEGER NOT NULL,
+        bookmarks           INTEGER,
+        url_clicks          INTEGER,
+        profile_clicks      INTEGER,
+        organic_impressions INTEGER,
+        organic_likes       INTEGER,
+        organic_retweets    INTEGER,
+        organic_replies     INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS tweet_metrics_tweet ON tweet_metrics (tweet_id, fetched_at);
+";
+
+/// Columns of `posts`, in the order `entry_from_row` reads them.
+const POST_COLUMNS: &str =
+    "action, account, tweet_id, url, text, media, reply_to, quote_of, thread_id, created_at";
+
+/// Columns of `tweet_metrics`, in the order `sample_from_row` reads them.
+const METRICS_COLUMNS: &str = "tweet_id, account, fetched_at, impressions, likes, retweets, \
+     replies, quotes, bookmarks, url_clicks, profile_clicks, organic_impressions, organic_likes, \
+     organic_retweets, organic_replies";
+
+/// Tweets and thread tweets that haven't been deleted since.
+const LIVE_POSTS: &str = "action IN ('tweet', 'thread') AND NOT EXISTS \
+     (SELECT 1 FROM posts d WHERE d.action = 'delete' AND d.tweet_id = posts.tweet_id)";
+
+/// One successful write to X.
+pub struct HistoryEntry {
+    pub action: HistoryAction,
+    pub account: String,
+    /// The posted tweet, or the tweet that was deleted, retweeted or liked.
+    pub tweet_id: String,
+    pub url: Option<String>,
+    /// Text as posted.
+    pub text: Option<String>,
+    /// Local paths of the uploaded media.
+    pub media: Vec<String>,
+    pub reply_to: Option<String>,
+    pub quote_of: Option<String>,
+    /// ID of a thread's first tweet, on every tweet of the thread.
+    pub thread_id: Option<String>,
+    pub created_at: u64,
+}
+
+impl HistoryEntry {
+    pub fn new(action: HistoryAction, account: &str, tweet_id: &str) -> Self {
+        Self {
+            action,
+            account: account.to_string(),
+            tweet_id: tweet_id.to_string(),
+            url: None,
+            text: None,
+            media: Vec::new(),
+            reply_to: None,
+            quote_of: None,
+            thread_id: None,
+            created_at: now_unix(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HistoryFilter<'a> {
+    pub account: Option<&'a str>,
+    pub action: Option<HistoryAction>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub contains: Option<&'a str>,
+    pub limit: u32,
+}
+
+/// SQLite log of everything this server posted, deleted, retweeted or liked.
+/// The database is opened on first use, so a broken path only affects history.
+pub struct PostHistory {
+    path: PathBuf,
+    conn: Mutex<Option<Connection>>,
+}
+
+impl PostHistory {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            conn: Mutex::new(None),
+        }
+    }
+
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, String> {
+        let mut guard = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            *guard = Some(self.open()?);
+        }
+        f(guard.as_ref().unwrap())
+            .map_err(|e| format!("Post history ({}): {e}", self.path.display()))
+    }
+
+    fn open(&self) -> Result<Connection, String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let conn = Connection::open(&self.path)
+            .and_then(|conn| {
+                // The MCP server and the daemon may write at the same time.
+                conn.busy_timeout(Duration::from_secs(5))?;
+                conn.pragma_update(None, "journal_mode", "WAL")?;
+                conn.execute_batch(SCHEMA)?;
+                Ok(conn)
+            })
+            .map_err(|e| format!("Failed to open {}: {e}", self.path.display()))?;
+        Ok(conn)
+    }
+
+    pub fn record(&self, entry: &HistoryEntry) -> Result<(), String> {
+        let media = serde_json::to_string(&entry.media).unwrap_or_else(|_| "[]".into());
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO posts (action, account, tweet_id, url, text, media, reply_to, \
+                 quote_of, thread_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                rusqlite::params![
+                    entry.action.as_str(),
+                    entry.account,
+                    entry.tweet_id,
+                    entry.url,
+                    entry.text,
+                    media,
+                    entry.reply_to,
+                    entry.quote_of,
+                    entry.thread_id,
+                    entry.created_at as i64,
+                ],
+            )
+            .map(|_| ())
+        })
+    }
+
+    /// Matching entries, newest first.
+    pub fn query(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>, String> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(account) = filter.account {
+            conditions.push("account = ?");
+            values.push(Value::Text(account.to_string()));
+        }
+        if let Some(action) = filter.action {
+            conditions.push("action = ?");
+            values.push(Value::Text(action.as_str().to_string()));
+        }
+        if let Some(since) = filter.since {
+            conditions.push("created_at >= ?");
+            values.push(Value::Integer(since as i64));
+        }
+        if let Some(until) = filter.until {
+            conditions.push("created_at <= ?");
+            values.push(Value::Integer(until as i64));
+        }
+        if let Some(contains) = filter.contains {
+            conditions.push("text LIKE ? ESCAPE '\\'");
+            let escaped = contains
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            values.push(Value::Text(format!("%{escaped}%")));
+        }
+        let mut sql = format!("SELECT {POST_COLUMNS} FROM posts");
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY created_at DESC, id DESC LIMIT ?");
+        values.push(Value::Integer(filter.limit.into()));
+
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    // --- Engagement metrics ---
+
+    /// Tweets posted at or after `since` that haven't been deleted, oldest first.
+    pub fn live_posts(
+        &self,
+        account: Option<&str>,
+        since: u64,
+    ) -> Result<Vec<HistoryEntry>, String> {
+        let sql = format!(
+            "SELECT {POST_COLUMNS} FROM posts WHERE {LIVE_POSTS} AND created_at >= ?1 \
+             AND (?2 IS NULL OR account = ?2) ORDER BY created_at, id"
+        );
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params![since as i64, account], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    pub fn record_metrics(&self, samples: &[MetricsSample]) -> Result<(), String> {
+        let sql = format!(
+            "INSERT INTO tweet_metrics ({METRICS_COLUMNS}) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)"
+        );
+        let opt = |v: Option<u64>| v.map(|v| v as i64);
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            {
+                let mut stmt = tx.prepare(&sql)?;
+                for s in samples {
+                    stmt.execute(rusqlite::params![
+                        s.tweet_id,
+                        s.account,
+                        s.fetched_at as i64,
+                        opt(s.impressions),
+                        s.likes as i64,
+                        s.retweets as i64,
+                        s.replies as i64,
+                        s.quotes as i64,
+                        opt(s.bookmarks),
+                        opt(s.url_clicks),
+                        opt(s.profile_clicks),
+                        opt(s.organic_impressions),
+                        opt(s.organic_likes),
+                        opt(s.organic_retweets),
+                        opt(s.organic_replies),
+                    ])?;
+                }
+            }
+            tx.commit()
+        })
+    }
+
+    /// The tweet or thread tweet with this ID, if it was posted by this server.
+    pub fn find_post(&self, tweet_id: &str) -> Result<Option<HistoryEntry>, String> {
+        let sql = format!(
+            "SELECT {POST_COLUMNS} FROM posts WHERE tweet_id = ?1 \
+             AND action IN ('tweet', 'thread') LIMIT 1"
+        );
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query_map([tweet_id], entry_from_row)?;
+            rows.next().transpose()
+        })
+    }
+
+    /// Every metrics sample of one tweet, oldest first.
+    pub fn metrics_for(&self, tweet_id: &str) -> Result<Vec<MetricsSample>, String> {
+        let sql = format!(
+            "SELECT {METRICS_COLUMNS} FROM tweet_metrics WHERE tweet_id = ?1 ORDER BY fetched_at"
+        );
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map([tweet_id], sample_from_row)?;
+            rows.collect()
+        })
+    }
+
+    /// Every metrics sample of the tweets `live_posts` returns, ordered by
+    /// tweet and then oldest first.
+    pub fn metrics_since(
+        &self,
+        account: Option<&str>,
+        since: u64,
+    ) -> Result<Vec<MetricsSample>, String> {
+        let sql = format!(
+            "SELECT {METRICS_COLUMNS} FROM tweet_metrics WHERE tweet_id IN \
+             (SELECT tweet_id FROM posts WHERE {LIVE_POSTS} AND created_at >= ?1 \
+             AND (?2 IS NULL OR account = ?2)) ORDER BY tweet_id, fetched_at"
+        );
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params![since as i64, account], sample_from_row)?;
+            rows.collect()
+        })
+    }
+}
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    let action: String = row.get(0)?;
+    let media: String = row.get(5)?;
+    Ok(HistoryEntry {
+        action: parse_action(&action),
+        account: row.get(1)?,
+        tweet_id: row.get(2)?,
+        url: row.get(3)?,
+        text: row.get(4)?,
+        media: serde_json::from_str(&media).unwrap_or_default(),
+        reply_to: row.get(6)?,
+        quote_of: row.get(7)?,
+        thread_id: row.get(8)?,
+        created_at: row.get::<_, i64>(9)?.max(0) as u64,
+    })
+}
+
+fn sample_from_row(row: &rusqlite::Row) -> rusqlite::Result<MetricsSample> {
+    let count = |i: usize| row.get::<_, i64>(i).map(|v| v.max(0) as u64);
+    let opt = |i: usize| {
+        row.get::<_, Option<i64>>(i)
+            .map(|v| v.map(|v| v.max(0) as u64))
+    };
+    Ok(MetricsSample {
+        tweet_id: row.get(0)?,
+        account: row.get(1)?,
+        fetched_at: count(2)?,
+        impressions: opt(3)?,
+        likes: count(4)?,
+        retweets: count(5)?,
+        replies: count(6)?,
+        quotes: count(7)?,
+        bookmarks: opt(8)?,
+        url_clicks: opt(9)?,
+        profile_clicks: opt(10)?,
+        organic_impressions: opt(11)?,
+        organic_likes: opt(12)?,
+        organic_retweets: opt(13)?,
+        organic_replies: opt(14)?,
+    })
+}
+
+fn parse_action(raw: &str) -> HistoryAction {
+    match raw {
+        "thread" => HistoryAction::Thread,
+        "delete" => HistoryAction::Delete,
+        "retweet" => HistoryAction::Retweet,
+        "like" => HistoryAction::Like,
+        _ => HistoryAction::Tweet,
+    }
+}
+
+/// Parse a `since`/`until` bound: RFC 3339, or a UTC date. A date `until`
+/// covers the whole day.
+pub fn parse_bound(raw: &str, end_of_day: bool) -> Result<u64, String> {
+    let raw = raw.trim();
+    let ts = if let Ok(t) = DateTime::parse_from_rfc3339(raw) {
+        t.timestamp()
+    } else if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        let time = if end_of_day {
+            date.and_hms_opt(23, 59, 59)
+        } else {
+            date.and_hms_opt(0, 0, 0)
+        };
+        time.map(|t| t.and_utc().timestamp()).unwrap_or_default()
+    } else {
+        return Err(format!(
+            "Invalid time '{raw}'. Use RFC 3339 (2025-06-01T09:00:00Z) or a date (2025-06-01)"
+        ));
+    };
+    Ok(u64::try_from(ts).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::new_id;
+
+    /// 2025-06-01T00:00:00Z
+    const JUNE_1: u64 = 1_748_736_000;
+
+    #[test]
+    fn date_bounds_cover_the_whole_day() {
+        assert_eq!(parse_bound("2025-06-01", false), Ok(JUNE_1));
+        assert_eq!(parse_bound(" 2025-06-01 ", true), Ok(JUNE_1 + 86_399));
+    }
+
+    #[test]
+    fn rfc3339_bounds_keep_their_offset() {
+        assert_eq!(
+            parse_bound("2025-06-01T09:00:00Z", false),
+            Ok(JUNE_1 + 9 * 3600)
+        );
+        // end_of_day only applies to plain dates.
+        assert_eq!(
+            parse_bound("2025-06-01T09:00:00Z", true),
+            Ok(JUNE_1 + 9 * 3600)
+        );
+        assert_eq!(
+            parse_bound("2025-06-01T11:00:00+02:00", false),
+            Ok(JUNE_1 + 9 * 3600)
+        );
+    }
+
+    #[test]
+    fn invalid_bounds_are_rejected() {
+        for raw in ["", "yesterday", "2025-13-01", "2025-06-01 09:00"] {
+            let err = parse_bound(raw, false).unwrap_err();
+            assert!(err.contains("Invalid time"), "{raw}: {err}");
+        }
+    }
+
+    #[test]
+    fn contains_filter_matches_wildcards_literally() {
+        let dir = std::env::temp_dir().join(new_id("post-x-history"));
+        let history = PostHistory::new(dir.join("history.db"));
+        for (id, text) in [
+            ("1", "50% off today"),
+            ("2", "500 off today"),
+            ("3", "snake_case"),
+            ("4", "snakeXcase"),
+            ("5", "back\\slash"),
+        ] {
+            let mut entry = HistoryEntry::new(HistoryAction::Tweet, "main", id);
+            entry.text = Some(text.to_string());
+            history.record(&entry).unwrap();
+        }
+        let matching = |contains: &str| {
+            let filter = HistoryFilter {
+                contains: Some(contains),
+                limit: 10,
+                ..Default::default()
+            };
+            let mut ids: Vec<String> = history
+                .query(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.tweet_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let (percent, underscore, backslash, plain) = (
+            matching("0%"),
+            matching("e_c"),
+            matching("k\\s"),
+            matching("OFF"),
+        );
+        drop(history);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(percent, ["1"]);
+        assert_eq!(underscore, ["3"]);
+        assert_eq!(backslash, ["5"]);
+        // LIKE stays case-insensitive for ASCII.
+        assert_eq!(plain, ["1", "2"]);
+    }
+}
@@ -0,0 +1,228 @@
+use reqwest::Url;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// Query parameters removed by `strip_tracking`.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "mc_cid", "mc_eid", "igshid",
+    "yclid", "twclid", "_hsenc", "_hsmi",
+];
+
+// --- Config ---
+
+/// Per-account link rewriting under `[accounts.<name>.links]`, applied to
+/// every http(s) URL in the tweet text before posting. Nothing is rewritten
+/// unless configured.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LinkRules {
+    /// Replace a link's host, e.g. `"staging.example.com" = "example.com"`.
+    /// Keys are lowercased on load to match the lowercased host they're looked up by.
+    #[serde(deserialize_with = "lowercase_keys")]
+    pub rewrite_domains: HashMap<String, String>,
+    /// Remove well-known click-tracking parameters (fbclid, gclid, ...).
+    pub strip_tracking: bool,
+    /// Extra query parameters to remove from every link.
+    pub strip_params: Vec<String>,
+    /// Domains (including subdomains) that get `utm` parameters appended.
+    pub utm_domains: Vec<String>,
+    /// Parameters added to links on `utm_domains`, e.g. `utm_source = "x"`.
+    /// Parameters the link already sets are left alone.
+    pub utm: BTreeMap<String, String>,
+}
+
+fn lowercase_keys<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let map = HashMap::<String, String>::deserialize(deserializer)?;
+    Ok(map
+        .into_iter()
+        .map(|(host, target)| (host.trim().to_ascii_lowercase(), target))
+        .collect())
+}
+
+impl LinkRules {
+    fn is_empty(&self) -> bool {
+        self.rewrite_domains.is_empty()
+            && !self.strip_tracking
+            && self.strip_params.is_empty()
+            && (self.utm_domains.is_empty() || self.utm.is_empty())
+    }
+}
+
+// --- Rewriting ---
+
+/// Rewrite every link in `text`. Returns the new text and one description per
+/// link that changed.
+pub fn apply(text: &str, rules: &LinkRules) -> (String, Vec<String>) {
+    if rules.is_empty() {
+        return (text.to_string(), Vec::new());
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut applied = Vec::new();
+    for token in text.split_inclusive(char::is_whitespace) {
+        let word = token.trim_end_matches(char::is_whitespace);
+        let whitespace = &token[word.len()..];
+        if !(word.starts_with("https://") || word.starts_with("http://")) {
+            output.push_str(token);
+            continue;
+        }
+        // Keep sentence punctuation after a link out of the URL.
+        let link = word.trim_end_matches(['.', ',', '!', '?', ':', ';', ')', '"', '\'']);
+        let trailing = &word[link.len()..];
+        match rewrite_link(link, rules) {
+            Some(rewritten) => {
+                applied.push(format!("links: {link} -> {rewritten}"));
+                output.push_str(&rewritten);
+            }
+            None => output.push_str(link),
+        }
+        output.push_str(trailing);
+        output.push_str(whitespace);
+    }
+    (output, applied)
+}
+
+/// The rewritten link, or `None` if no rule changed it.
+fn rewrite_link(link: &str, rules: &LinkRules) -> Option<String> {
+    let mut url = Url::parse(link).ok()?;
+    let mut changed = false;
+
+    if let Some(host) = url.host_str().map(str::to_ascii_lowercase)
+        && let Some(target) = rules.rewrite_domains.get(&host)
+        && url.set_host(Some(target)).is_ok()
+    {
+        changed = true;
+    }
+
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    let ours = rules.utm_domains.iter().any(|d| {
+        let d = d.trim().to_ascii_lowercase();
+        host == d || host.ends_with(&format!(".{d}"))
+    });
+
+    let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let before = pairs.len();
+    pairs.retain(|(key, _)| {
+        let key = key.to_ascii_lowercase();
+        // Click IDs on the account's own links are its own attribution data.
+        let tracking = rules.strip_tracking && !ours && TRACKING_PARAMS.contains(&key.as_str());
+        let listed = rules
+            .strip_params
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(&key));
+        !(tracking || listed)
+    });
+    let mut query_changed = pairs.len() != before;
+
+    if ours {
+        for (key, value) in &rules.utm {
+            if !pairs.iter().any(|(k, _)| k == key) {
+                pairs.push((key.clone(), value.clone()));
+                query_changed = true;
+            }
+        }
+    }
+
+    if query_changed {
+        if pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(&pairs);
+        }
+        changed = true;
+    }
+    if !changed {
+        return None;
+    }
+
+    let mut rewritten = url.to_string();
+    // `Url` adds a "/" path to bare domains; don't introduce one the author didn't write.
+    let authored_path = link
+        .split_once("://")
+        .is_some_and(|(_, rest)| rest.contains('/'));
+    if !authored_path
+        && url.path() == "/"
+        && let Some(slash) = rewritten
+            .find("://")
+            .and_then(|i| rewritten[i + 3..].find('/').map(|j| i + 3 + j))
+    {
+        rewritten.remove(slash);
+    }
+    Some(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(toml: &str) -> LinkRules {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn no_rules_leaves_text_alone() {
+        let text = "See https://example.com/?fbclid=1";
+        assert_eq!(
+            apply(text, &LinkRules::default()),
+            (text.to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn strips_tracking_and_listed_params() {
+        let rules = rules("strip_tracking = true\nstrip_params = [\"ref\"]");
+        let (text, applied) = apply(
+            "Read https://example.com/post?id=7&fbclid=abc&REF=x, then reply",
+            &rules,
+        );
+        assert_eq!(text, "Read https://example.com/post?id=7, then reply");
+        assert_eq!(applied.len(), 1);
+    }
+
+    #[test]
+    fn removing_every_param_drops_the_query() {
+        let rules = rules("strip_tracking = true");
+        let (text, _) = apply("https://example.com/a?gclid=1", &rules);
+        assert_eq!(text, "https://example.com/a");
+    }
+
+    #[test]
+    fn utm_is_added_to_own_domains_only() {
+        let rules = rules(
+            "utm_domains = [\"Example.com\"]\n[utm]\nutm_source = \"x\"\nutm_medium = \"social\"",
+        );
+        let (text, _) = apply(
+            "https://blog.example.com/p https://other.org/p https://example.com/?utm_source=nl",
+            &rules,
+        );
+        assert_eq!(
+            text,
+            "https://blog.example.com/p?utm_medium=social&utm_source=x https://other.org/p \
+             https://example.com/?utm_source=nl&utm_medium=social"
+        );
+    }
+
+    #[test]
+    fn own_domains_keep_click_ids() {
+        let rules = rules("strip_tracking = true\nutm_domains = [\"example.com\"]");
+        let (text, applied) = apply("https://example.com/p?fbclid=1", &rules);
+        assert_eq!(text, "https://example.com/p?fbclid=1");
+        assert!(applied.is_empty());
+    }
+
+    #[test]
+    fn rewrites_domains_case_insensitively() {
+        let rules = rules("[rewrite_domains]\n\"Staging.Example.com\" = \"example.com\"");
+        let (text, _) = apply("Try https://STAGING.example.com/new.", &rules);
+        assert_eq!(text, "Try https://example.com/new.");
+    }
+
+    #[test]
+    fn bare_domains_dont_gain_a_slash() {
+        let rules = rules("[rewrite_domains]\n\"old.io\" = \"new.io\"");
+        let (text, _) = apply("Go to https://old.io!", &rules);
+        assert_eq!(text, "Go to https://new.io!");
+    }
+}
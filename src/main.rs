@@ -1,10 +1,25 @@
 mod api;
+mod audit;
+mod dedupe;
+mod engagement;
+mod export;
+mod history;
+mod links;
+mod mentions;
 mod params;
+mod recurring;
 mod server;
+mod snapshots;
+mod store;
+mod template;
+mod text;
+mod thread_file;
 
 use api::AppConfig;
+use params::{OnFailure, PostThreadFromFileParams};
 use rmcp::{ServiceExt, transport::stdio};
 use server::PostXServer;
+use store::FileLock;
 use std::path::PathBuf;
 use tracing_subscriber::EnvFilter;
 
@@ -36,14 +51,114 @@ fn load_config() -> Result<AppConfig, Box<dyn std::error::Error>> {
         .map_err(|e| format!("Config error at {}: {e}", path.display()))?;
 
     tracing::info!(
-        "Config loaded: {} account(s), default='{}' from {}",
+        "Config loaded: {} account(s), default='{}' from {}, data dir {}",
         config.accounts.len(),
         config.default_account,
-        path.display()
+        path.display(),
+        config.data_dir.display()
     );
     Ok(config)
 }
 
+const THREAD_USAGE: &str = "Usage: post-x thread <file.md> [--account NAME] [--reply-to ID] \
+                            [--rollback] [--dry-run]";
+
+fn parse_thread_args(args: &[String]) -> Result<PostThreadFromFileParams, String> {
+    let mut params = PostThreadFromFileParams {
+        path: String::new(),
+        account: None,
+        reply_to: None,
+        on_failure: None,
+        dry_run: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--account" => params.account = iter.next().cloned(),
+            "--reply-to" => params.reply_to = iter.next().cloned(),
+            "--rollback" => params.on_failure = Some(OnFailure::Rollback),
+            "--dry-run" => params.dry_run = Some(true),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {flag}")),
+            path if params.path.is_empty() => params.path = path.to_string(),
+            extra => return Err(format!("Unexpected argument {extra}")),
+        }
+    }
+    if params.path.is_empty() {
+        return Err("Missing Markdown file path".into());
+    }
+    Ok(params)
+}
+
+/// `post-x thread <file.md>`: post a Markdown thread file without an MCP client.
+async fn run_thread_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let params = parse_thread_args(args).map_err(|e| format!("{e}\n{THREAD_USAGE}"))?;
+    let server = PostXServer::new(load_config()?);
+    let arguments = serde_json::json!({
+        "path": params.path,
+        "account": params.account,
+        "reply_to": params.reply_to,
+        "on_failure": params.on_failure,
+        "dry_run": params.dry_run,
+    });
+    let started_at = chrono::Utc::now();
+    let result = server.post_thread_file(params).await;
+    server.audit("cli:thread", arguments, started_at, &Ok(result.clone()));
+    for content in &result.content {
+        if let Some(text) = content.as_text() {
+            println!("{}", text.text);
+        }
+    }
+    if result.is_error == Some(true) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `post-x daemon`: run the schedule queue and other background jobs without
+/// an MCP client, until SIGTERM or Ctrl-C.
+async fn run_daemon() -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let lock_path = config.data_dir.join("daemon.lock");
+    let mut lock = FileLock::try_acquire(&lock_path)?.ok_or_else(|| {
+        format!(
+            "Another post-x daemon is already running (lock held on {})",
+            lock_path.display()
+        )
+    })?;
+    lock.write_pid()?;
+    let shutdown = shutdown_signal()?;
+
+    let server = PostXServer::new(config);
+    tracing::info!("post-x daemon started (pid {})", std::process::id());
+    server.run_background_jobs(shutdown).await;
+    tracing::info!("post-x daemon stopped");
+    Ok(())
+}
+
+/// Install the SIGTERM and Ctrl-C handlers, returning a future that resolves on
+/// either. Installed up front so a signal during the first tick isn't missed.
+#[cfg(unix)]
+fn shutdown_signal() -> std::io::Result<impl Future<Output = ()>> {
+    use tokio::signal::unix::{SignalKind, signal};
+    let mut term = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    Ok(async move {
+        tokio::select! {
+            _ = term.recv() => tracing::info!("Received SIGTERM, shutting down"),
+            _ = interrupt.recv() => tracing::info!("Received Ctrl-C, shutting down"),
+        }
+    })
+}
+
+#[cfg(not(unix))]
+fn shutdown_signal() -> std::io::Result<impl Future<Output = ()>> {
+    let mut ctrl_c = tokio::signal::windows::ctrl_c()?;
+    Ok(async move {
+        ctrl_c.recv().await;
+        tracing::info!("Received Ctrl-C, shutting down");
+    })
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt()
@@ -51,8 +166,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_writer(std::io::stderr)
         .init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("thread") => return run_thread_command(&args[1..]).await,
+        Some("daemon") => return run_daemon().await,
+        _ => {}
+    }
+
     let config = load_config()?;
     let server = PostXServer::new(config);
+    server.start_background_jobs();
     let service = server.serve(stdio()).await?;
     service.waiting().await?;
     Ok(())
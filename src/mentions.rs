@@ -0,0 +1,181 @@
+use crate::api::XClient;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a handle lookup is trusted before it is checked again.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Longest handle X allows.
+const MAX_HANDLE_LEN: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MentionStatus {
+    Active,
+    Protected,
+    Suspended,
+    NotFound,
+}
+
+/// Outcome of checking every @mention in a tweet.
+#[derive(Default)]
+pub struct MentionReport {
+    /// Handles that don't exist, are suspended or are protected.
+    pub problems: Vec<String>,
+    /// Handles that could not be checked (rate limit, network error).
+    pub unverified: Vec<String>,
+}
+
+/// Resolves @mentions before posting, caching results per handle so repeated
+/// posts mentioning the same accounts don't burn the user lookup rate limit.
+pub struct MentionChecker {
+    cache: Mutex<HashMap<String, (MentionStatus, Instant)>>,
+}
+
+impl MentionChecker {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn check(&self, client: &XClient, text: &str) -> MentionReport {
+        let mut report = MentionReport::default();
+        for handle in extract_mentions(text) {
+            if handle.len() > MAX_HANDLE_LEN {
+                report.problems.push(format!(
+                    "@{handle} is not a valid handle (longer than {MAX_HANDLE_LEN} characters)"
+                ));
+                continue;
+            }
+            let status = match self.cached(&handle) {
+                Some(status) => status,
+                None => match lookup(client, &handle).await {
+                    Ok(status) => {
+                        self.cache_lock()
+                            .insert(handle.to_lowercase(), (status, Instant::now()));
+                        status
+                    }
+                    Err(e) => {
+                        report.unverified.push(format!("@{handle}: {e}"));
+                        continue;
+                    }
+                },
+            };
+            match status {
+                MentionStatus::Active => {}
+                MentionStatus::NotFound => {
+                    report.problems.push(format!("@{handle} does not exist"));
+                }
+                MentionStatus::Suspended => {
+                    report.problems.push(format!("@{handle} is suspended"));
+                }
+                MentionStatus::Protected => report.problems.push(format!(
+                    "@{handle} is protected (only their approved followers see their replies)"
+                )),
+            }
+        }
+        report
+    }
+
+    fn cached(&self, handle: &str) -> Option<MentionStatus> {
+        self.cache_lock()
+            .get(&handle.to_lowercase())
+            .filter(|(_, checked)| checked.elapsed() < CACHE_TTL)
+            .map(|(status, _)| *status)
+    }
+
+    fn cache_lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (MentionStatus, Instant)>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+async fn lookup(client: &XClient, handle: &str) -> Result<MentionStatus, String> {
+    match client.lookup_user_by_username(handle).await {
+        Ok(profile) if profile.protected == Some(true) => Ok(MentionStatus::Protected),
+        Ok(_) => Ok(MentionStatus::Active),
+        Err(e) if e.contains("suspended") => Ok(MentionStatus::Suspended),
+        Err(e) if e.starts_with("User not found") => Ok(MentionStatus::NotFound),
+        Err(e) => Err(e),
+    }
+}
+
+/// Unique @handles in order of appearance (case-insensitive). Follows X's rule
+/// that a mention can't directly follow a letter, digit or certain symbols, so
+/// email addresses and URLs like `medium.com/@name` are not mentions.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut handles: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let can_start = !prev.is_some_and(|p| {
+            p.is_alphanumeric() || matches!(p, '_' | '!' | '@' | '#' | '$' | '%' | '&' | '*' | '/')
+        });
+        prev = Some(c);
+        if !(matches!(c, '@' | '＠') && can_start) {
+            continue;
+        }
+        let start = i + c.len_utf8();
+        let mut end = start;
+        while let Some(&(j, n)) = chars.peek() {
+            if !(n.is_ascii_alphanumeric() || n == '_') {
+                break;
+            }
+            end = j + n.len_utf8();
+            prev = Some(n);
+            chars.next();
+        }
+        let handle = &text[start..end];
+        if !handle.is_empty() && !handles.iter().any(|h| h.eq_ignore_ascii_case(handle)) {
+            handles.push(handle.to_string());
+        }
+    }
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_handles_in_order() {
+        assert_eq!(
+            extract_mentions("Hi @alice and @bob_2!"),
+            ["alice", "bob_2"]
+        );
+        assert_eq!(
+            extract_mentions("(@erin) @frank, thanks"),
+            ["erin", "frank"]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_case_insensitive() {
+        assert_eq!(extract_mentions("@Alice @alice @ALICE"), ["Alice"]);
+    }
+
+    #[test]
+    fn fullwidth_at_sign_counts() {
+        assert_eq!(extract_mentions("＠carol hello"), ["carol"]);
+    }
+
+    #[test]
+    fn emails_and_url_paths_are_not_mentions() {
+        assert!(extract_mentions("mail me@example.com").is_empty());
+        assert!(extract_mentions("see https://example.com/@dave").is_empty());
+        assert!(extract_mentions("@@twice and #@tag").is_empty());
+    }
+
+    #[test]
+    fn bare_at_sign_is_ignored() {
+        assert!(extract_mentions("meet @ noon").is_empty());
+    }
+
+    #[test]
+    fn overlong_handles_are_still_extracted() {
+        assert_eq!(
+            extract_mentions("@abcdefghijklmnopq"),
+            ["abcdefghijklmnopq"]
+        );
+    }
+}
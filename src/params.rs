@@ -1,8 +1,9 @@
 use crate::api::MediaAttachment;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct MediaAttachmentParam {
     #[schemars(description = "Local file path to the media file (jpeg, png, gif, webp, mp4)")]
     pub path: String,
@@ -59,6 +60,84 @@ pub struct PostTweetParams {
         description = "Tweet ID to reply to (e.g. '123456'). When set, the tweet is posted as a reply to the specified tweet."
     )]
     pub reply_to: Option<String>,
+    #[schemars(
+        description = "Tweet ID or URL to quote. The quoted tweet is embedded below this tweet's text."
+    )]
+    pub quote_tweet_id: Option<String>,
+    #[schemars(
+        description = "Post even if an identical tweet (same text, media, reply and quote target) was posted recently from this account"
+    )]
+    pub allow_duplicate: Option<bool>,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PostToAccountsParams {
+    #[schemars(description = "Account names to post from (at least one)")]
+    pub accounts: Vec<String>,
+    #[schemars(description = "The tweet text")]
+    pub text: String,
+    #[schemars(
+        description = "Media attachments to upload and attach (max 4 images, or 1 video, or 1 GIF). Each file is uploaded once per account."
+    )]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(
+        description = "Post even if an identical tweet (same text, media and reply target) was posted recently from an account"
+    )]
+    pub allow_duplicate: Option<bool>,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request each account would send, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PostFromTemplateParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(description = "Name of a template from the [templates] config section")]
+    pub template: String,
+    #[schemars(
+        description = "Values for the template's {placeholders}, e.g. {\"version\": \"1.4.0\"}. Placeholders with a configured default may be omitted."
+    )]
+    pub variables: Option<HashMap<String, String>>,
+    #[schemars(
+        description = "Media attachments to use instead of the template's default media (max 4 images, or 1 video, or 1 GIF)"
+    )]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(description = "Tweet ID to reply to (e.g. '123456')")]
+    pub reply_to: Option<String>,
+    #[schemars(
+        description = "Post even if an identical tweet (same text, media and reply target) was posted recently from this account"
+    )]
+    pub allow_duplicate: Option<bool>,
+    #[schemars(
+        description = "If true, render the template, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EditTweetParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "The ID or URL of the tweet to edit (e.g. '123456' or 'https://x.com/user/status/123456')"
+    )]
+    pub tweet_id: String,
+    #[schemars(description = "The new tweet text")]
+    pub text: String,
+    #[schemars(
+        description = "Media attachments to upload and attach to the new version (max 4 images, or 1 video, or 1 GIF). Cannot be used with media_ids."
+    )]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(
+        description = "Pre-uploaded media IDs to attach to the new version (max 4). Cannot be used with media."
+    )]
+    pub media_ids: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -77,6 +156,339 @@ pub struct PostThreadParams {
         description = "Array of tweets to post as a thread (max 25). Each tweet has 'text' and optional 'media'."
     )]
     pub tweets: Vec<ThreadTweet>,
+    #[schemars(
+        description = "Tweet ID or URL the first tweet replies to. Use it to continue an existing thread or post a thread in reply to someone."
+    )]
+    pub reply_to: Option<String>,
+    #[schemars(
+        description = "What to do if a tweet fails: 'stop' (default, keep posted tweets so the thread can be resumed) or 'rollback' (delete every tweet already posted in this thread, newest first)"
+    )]
+    pub on_failure: Option<OnFailure>,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PostThreadFromFileParams {
+    #[schemars(
+        description = "Path to a Markdown file. Tweets are separated by lines containing only '---'; images are written as ![alt text](relative/path.png)."
+    )]
+    pub path: String,
+    #[schemars(
+        description = "Account name to use. Overrides the file's front-matter; omit to use the front-matter or the default account."
+    )]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Tweet ID or URL the first tweet replies to. Overrides the file's front-matter."
+    )]
+    pub reply_to: Option<String>,
+    #[schemars(
+        description = "What to do if a tweet fails: 'stop' (default, keep posted tweets so the thread can be resumed) or 'rollback' (delete every tweet already posted in this thread, newest first)"
+    )]
+    pub on_failure: Option<OnFailure>,
+    #[schemars(
+        description = "If true, parse and validate the file and return the exact requests that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    #[default]
+    Stop,
+    Rollback,
+}
+
+/// What the schedule worker does with a post whose time passed while nothing was running.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedPolicy {
+    #[default]
+    PostLate,
+    Skip,
+}
+
+/// Kind of action recorded in the post history.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryAction {
+    Tweet,
+    Thread,
+    Delete,
+    Retweet,
+    Like,
+}
+
+impl HistoryAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tweet => "tweet",
+            Self::Thread => "thread",
+            Self::Delete => "delete",
+            Self::Retweet => "retweet",
+            Self::Like => "like",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleTweetParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(description = "The tweet text")]
+    pub text: String,
+    #[schemars(
+        description = "Media attachments to upload when the tweet is posted (max 4 images, or 1 video, or 1 GIF). Files must still exist at posting time."
+    )]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(description = "Tweet ID or URL to reply to")]
+    pub reply_to: Option<String>,
+    #[schemars(
+        description = "When to post, as an RFC 3339 timestamp with offset (e.g. '2025-06-01T09:00:00+02:00')"
+    )]
+    pub post_at: String,
+    #[schemars(
+        description = "If the time passes while no server or daemon is running: 'post_late' (default) or 'skip'"
+    )]
+    pub if_missed: Option<MissedPolicy>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleThreadParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Array of tweets to post as a thread (max 25). Each tweet has 'text' and optional 'media'."
+    )]
+    pub tweets: Vec<ThreadTweet>,
+    #[schemars(description = "Tweet ID or URL the first tweet replies to")]
+    pub reply_to: Option<String>,
+    #[schemars(
+        description = "When to post, as an RFC 3339 timestamp with offset (e.g. '2025-06-01T09:00:00+02:00')"
+    )]
+    pub post_at: String,
+    #[schemars(
+        description = "If the time passes while no server or daemon is running: 'post_late' (default) or 'skip'"
+    )]
+    pub if_missed: Option<MissedPolicy>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListScheduledParams {
+    #[schemars(description = "Only list posts for this account (omit for all accounts)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Also list posted, failed, skipped and cancelled jobs (default false: pending only)"
+    )]
+    pub include_finished: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListRecurringParams {
+    #[schemars(description = "Only list jobs for this account (omit for all accounts)")]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelScheduledParams {
+    #[schemars(description = "Scheduled post ID from schedule_tweet, schedule_thread or list_scheduled")]
+    pub job_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SaveDraftParams {
+    #[schemars(description = "Account the draft will be posted from (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(description = "Tweet text, for a single-tweet draft. Set either text or tweets.")]
+    pub text: Option<String>,
+    #[schemars(
+        description = "Media for a single-tweet draft (max 4 images, or 1 video, or 1 GIF). Files are uploaded when the draft is published."
+    )]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(
+        description = "Tweets for a thread draft (max 25). Each tweet has 'text' and optional 'media'. Set either text or tweets."
+    )]
+    pub tweets: Option<Vec<ThreadTweet>>,
+    #[schemars(description = "Tweet ID or URL the draft (or the thread's first tweet) replies to")]
+    pub reply_to: Option<String>,
+    #[schemars(description = "Tweet ID or URL to quote (single-tweet drafts only)")]
+    pub quote_tweet_id: Option<String>,
+    #[schemars(description = "Note for the reviewer, e.g. why the draft was written. Never posted.")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListDraftsParams {
+    #[schemars(description = "Only list drafts for this account (omit for all accounts)")]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateDraftParams {
+    #[schemars(description = "Draft ID from save_draft or list_drafts")]
+    pub draft_id: String,
+    #[schemars(description = "Move the draft to this account")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "New tweet text. On a thread draft, turns it into a single-tweet draft."
+    )]
+    pub text: Option<String>,
+    #[schemars(description = "Replace the media of a single-tweet draft (empty array removes it)")]
+    pub media: Option<Vec<MediaAttachmentParam>>,
+    #[schemars(
+        description = "Replace the tweets. On a single-tweet draft, turns it into a thread draft."
+    )]
+    pub tweets: Option<Vec<ThreadTweet>>,
+    #[schemars(description = "New reply target (empty string removes it)")]
+    pub reply_to: Option<String>,
+    #[schemars(description = "New quoted tweet (empty string removes it)")]
+    pub quote_tweet_id: Option<String>,
+    #[schemars(description = "New reviewer note (empty string removes it)")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DraftIdParams {
+    #[schemars(description = "Draft ID from save_draft or list_drafts")]
+    pub draft_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PublishDraftParams {
+    #[schemars(description = "Draft ID from save_draft or list_drafts")]
+    pub draft_id: String,
+    #[schemars(
+        description = "Post even if an identical tweet was posted recently from this account (single-tweet drafts)"
+    )]
+    pub allow_duplicate: Option<bool>,
+    #[schemars(
+        description = "For thread drafts, what to do if a tweet fails: 'stop' (default) or 'rollback'"
+    )]
+    pub on_failure: Option<OnFailure>,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X. The draft is kept."
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPostHistoryParams {
+    #[schemars(description = "Only show this account's history (omit for all accounts)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Only show this kind of action: 'tweet', 'thread', 'delete', 'retweet' or 'like'"
+    )]
+    pub action: Option<HistoryAction>,
+    #[schemars(
+        description = "Only show entries at or after this time: RFC 3339 (e.g. '2025-06-01T09:00:00Z') or a UTC date ('2025-06-01')"
+    )]
+    pub since: Option<String>,
+    #[schemars(
+        description = "Only show entries at or before this time: RFC 3339 or a UTC date (the whole day is included)"
+    )]
+    pub until: Option<String>,
+    #[schemars(description = "Only show entries whose text contains this (case-insensitive)")]
+    pub contains: Option<String>,
+    #[schemars(description = "Maximum entries to return, newest first (default 50, max 1000)")]
+    pub limit: Option<u32>,
+}
+
+/// Which of an account's follow lists a snapshot holds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowList {
+    Followers,
+    Following,
+}
+
+impl FollowList {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Followers => "followers",
+            Self::Following => "following",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SnapshotFollowersParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Which list to snapshot: 'followers' or 'following' (omit to snapshot both)"
+    )]
+    pub list: Option<FollowList>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FollowerChangesParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(description = "Which list to compare: 'followers' (default) or 'following'")]
+    pub list: Option<FollowList>,
+    #[schemars(
+        description = "Compare against the newest snapshot taken at or before this time: RFC 3339 or a UTC date. Default: the snapshot before the latest one"
+    )]
+    pub since: Option<String>,
+    #[schemars(
+        description = "Take a fresh snapshot first and compare it (default false: compare existing snapshots only)"
+    )]
+    pub refresh: Option<bool>,
+}
+
+/// File format for `export_path`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetAllFollowsParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Write every user to this local file instead of listing them, and return only the path and row count (e.g. 'followers.csv'). Relative paths are resolved under <data_dir>/exports; absolute paths must be inside the data directory"
+    )]
+    pub export_path: Option<String>,
+    #[schemars(
+        description = "Export file format: 'csv' or 'jsonl' (default: from the export_path extension, else jsonl)"
+    )]
+    pub format: Option<ExportFormat>,
+    #[schemars(description = "Replace export_path if the file already exists (default: false)")]
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TweetPerformanceParams {
+    #[schemars(description = "Only report this account's tweets (omit for all accounts)")]
+    pub account: Option<String>,
+    #[schemars(description = "Report on tweets posted in the last N days (default 7)")]
+    pub days: Option<u64>,
+    #[schemars(
+        description = "Show every stored sample of this one tweet (ID or URL) instead of the summary"
+    )]
+    pub tweet_id: Option<String>,
+    #[schemars(description = "Number of top posts to list (default 5, max 50)")]
+    pub top: Option<usize>,
+    #[schemars(
+        description = "Leave tweets with fewer impressions than this out of the top posts, so a handful of views doesn't top the list (default 100)"
+    )]
+    pub min_impressions: Option<u64>,
+    #[schemars(
+        description = "Fetch current metrics from X before reporting (default false: use stored samples)"
+    )]
+    pub refresh: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResumeThreadParams {
+    #[schemars(description = "Thread job ID reported by post_thread when a tweet failed")]
+    pub job_id: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -89,6 +501,20 @@ pub struct TimelineParams {
     pub exclude: Option<String>,
     #[schemars(description = "Pagination token from a previous response to get the next page")]
     pub pagination_token: Option<String>,
+    #[schemars(
+        description = "Write the tweets to this local file instead of listing them, and return only the path and row count (e.g. 'results.jsonl'). Relative paths are resolved under <data_dir>/exports; absolute paths must be inside the data directory"
+    )]
+    pub export_path: Option<String>,
+    #[schemars(
+        description = "Export file format: 'csv' or 'jsonl' (default: from the export_path extension, else jsonl)"
+    )]
+    pub format: Option<ExportFormat>,
+    #[schemars(description = "Replace export_path if the file already exists (default: false)")]
+    pub overwrite: Option<bool>,
+    #[schemars(
+        description = "With export_path: stop after this many tweets (1-10000, default 1000). Pages of up to 100 are fetched until the results run out or this is reached, starting at pagination_token if given"
+    )]
+    pub max_rows: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -111,6 +537,10 @@ pub struct SendDmParams {
     pub conversation_id: String,
     #[schemars(description = "The message text to send")]
     pub text: String,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -129,6 +559,20 @@ pub struct SearchTweetsParams {
     pub sort_order: Option<String>,
     #[schemars(description = "Pagination token from a previous response to get the next page")]
     pub pagination_token: Option<String>,
+    #[schemars(
+        description = "Write the tweets to this local file instead of listing them, and return only the path and row count (e.g. 'results.jsonl'). Relative paths are resolved under <data_dir>/exports; absolute paths must be inside the data directory"
+    )]
+    pub export_path: Option<String>,
+    #[schemars(
+        description = "Export file format: 'csv' or 'jsonl' (default: from the export_path extension, else jsonl)"
+    )]
+    pub format: Option<ExportFormat>,
+    #[schemars(description = "Replace export_path if the file already exists (default: false)")]
+    pub overwrite: Option<bool>,
+    #[schemars(
+        description = "With export_path: stop after this many tweets (1-10000, default 1000). Pages of up to 100 are fetched until the results run out or this is reached, starting at pagination_token if given"
+    )]
+    pub max_rows: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -141,6 +585,58 @@ pub struct TweetIdParams {
     pub tweet_id: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTweetParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "Tweet IDs or tweet URLs to look up (1-100, e.g. ['123456', 'https://x.com/user/status/789012'])"
+    )]
+    pub tweet_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UnrollThreadParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "ID or URL of any tweet in the thread (e.g. '123456' or 'https://x.com/user/status/123456')"
+    )]
+    pub tweet_id: String,
+    #[schemars(
+        description = "Optional local file path to write the Markdown to (e.g. 'thread.md'). Relative paths are resolved under <data_dir>/unrolled; absolute paths must be inside the data directory"
+    )]
+    pub output_path: Option<String>,
+    #[schemars(description = "Replace output_path if the file already exists (default: false)")]
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteTweetParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(
+        description = "The tweet ID or tweet URL (e.g. '123456' or 'https://x.com/user/status/123456')"
+    )]
+    pub tweet_id: String,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FollowUserParams {
+    #[schemars(description = "Account name to use (omit for default account)")]
+    pub account: Option<String>,
+    #[schemars(description = "Username (with or without @) or numeric user ID")]
+    pub user: String,
+    #[schemars(
+        description = "If true, run all local validation and return the exact request that would be sent, without calling X"
+    )]
+    pub dry_run: Option<bool>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct LookupUserParams {
     #[schemars(description = "Account name to use (omit for default account)")]
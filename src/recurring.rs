@@ -0,0 +1,409 @@
+use crate::api::MediaAttachment;
+use crate::params::MissedPolicy;
+use crate::text::validate_tweet_text;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+// --- Config ---
+
+/// Recurring posts, evergreen queues and holidays from `config.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct RecurringConfig {
+    pub jobs: Vec<RecurringJob>,
+    pub evergreen: HashMap<String, EvergreenQueue>,
+    /// Dates on which recurring jobs with `skip_holidays` don't post.
+    pub holidays: HashSet<NaiveDate>,
+}
+
+/// A post that repeats on a cron schedule, from a `[[recurring]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecurringJob {
+    pub name: String,
+    pub account: Option<String>,
+    /// Standard 5-field cron expression, e.g. `"0 10 * * TUE"`.
+    pub cron: String,
+    /// IANA time zone the cron expression is evaluated in.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Fixed tweet text. Exactly one of `text` and `evergreen` is set.
+    pub text: Option<String>,
+    #[serde(default)]
+    pub media: Vec<MediaAttachment>,
+    /// Name of the `[evergreen.<name>]` queue to take the next post from.
+    pub evergreen: Option<String>,
+    #[serde(default = "default_true")]
+    pub skip_holidays: bool,
+    #[serde(default)]
+    pub if_missed: MissedPolicy,
+}
+
+/// A rotating list of posts that are reused, but never within `min_days_between_repeats`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvergreenQueue {
+    #[serde(default = "default_min_days")]
+    pub min_days_between_repeats: u64,
+    pub posts: Vec<EvergreenPost>,
+}
+
+/// An evergreen post: plain text, or a table with text and media.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EvergreenPost {
+    Text(String),
+    Post {
+        text: String,
+        #[serde(default)]
+        media: Vec<MediaAttachment>,
+    },
+}
+
+pub fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_min_days() -> u64 {
+    30
+}
+
+/// Names double as state file names, so they are kept to a safe charset.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl RecurringConfig {
+    /// Validate the raw config sections. `accounts` maps each account name to
+    /// its `max_tweet_length`; jobs without an account post as `default_account`.
+    pub fn from_raw(
+        jobs: Vec<RecurringJob>,
+        evergreen: HashMap<String, EvergreenQueue>,
+        holidays: &[String],
+        accounts: &HashMap<&str, usize>,
+        default_account: &str,
+    ) -> Result<Self, String> {
+        let holidays = holidays
+            .iter()
+            .map(|d| {
+                NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid holiday '{d}' (expected YYYY-MM-DD): {e}"))
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        for (name, queue) in &evergreen {
+            if !is_valid_name(name) {
+                return Err(format!(
+                    "Evergreen queue name '{name}' may only contain letters, digits, '-' and '_'"
+                ));
+            }
+            if queue.posts.is_empty() {
+                return Err(format!("Evergreen queue '{name}' has no posts"));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for job in &jobs {
+            let name = &job.name;
+            if !is_valid_name(name) {
+                return Err(format!(
+                    "Recurring job name '{name}' may only contain letters, digits, '-' and '_'"
+                ));
+            }
+            if !names.insert(name) {
+                return Err(format!("Duplicate recurring job name '{name}'"));
+            }
+            job.schedule()
+                .map_err(|e| format!("Recurring job '{name}': {e}"))?;
+            let account = job.account.as_deref().unwrap_or(default_account);
+            let Some(&max_length) = accounts.get(account) else {
+                return Err(format!(
+                    "Recurring job '{name}': unknown account '{account}'"
+                ));
+            };
+            match (&job.text, &job.evergreen) {
+                (Some(text), None) => validate_tweet_text(text, max_length)
+                    .map_err(|e| format!("Recurring job '{name}': {e}"))?,
+                (None, Some(queue)) => {
+                    let Some(posts) = evergreen.get(queue).map(|q| &q.posts) else {
+                        return Err(format!(
+                            "Recurring job '{name}': unknown evergreen queue '{queue}'"
+                        ));
+                    };
+                    // Checked per job, against the limit of the account it posts as.
+                    for (i, post) in posts.iter().enumerate() {
+                        validate_tweet_text(post.text(), max_length).map_err(|e| {
+                            format!(
+                                "Evergreen queue '{queue}' post {} (used by recurring job \
+                                 '{name}'): {e}",
+                                i + 1
+                            )
+                        })?;
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "Recurring job '{name}': set exactly one of 'text' and 'evergreen'"
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            jobs,
+            evergreen,
+            holidays,
+        })
+    }
+}
+
+// --- Schedule ---
+
+pub struct Schedule {
+    cron: Cron,
+    tz: Tz,
+}
+
+impl RecurringJob {
+    pub fn schedule(&self) -> Result<Schedule, String> {
+        Schedule::parse(&self.cron, &self.timezone)
+    }
+
+    /// Identifies the schedule, so saved state is reset when the config changes.
+    pub fn schedule_key(&self) -> String {
+        format!("{} {}", self.cron, self.timezone)
+    }
+}
+
+impl Schedule {
+    pub fn parse(cron: &str, timezone: &str) -> Result<Self, String> {
+        let parsed =
+            Cron::from_str(cron).map_err(|e| format!("invalid cron expression '{cron}': {e}"))?;
+        let tz = timezone
+            .parse::<Tz>()
+            .map_err(|_| format!("unknown time zone '{timezone}'"))?;
+        Ok(Self { cron: parsed, tz })
+    }
+
+    /// First occurrence strictly after `unix`.
+    pub fn next_after(&self, unix: u64) -> Option<u64> {
+        let start = DateTime::<Utc>::from_timestamp(unix as i64, 0)?.with_timezone(&self.tz);
+        let next = self.cron.find_next_occurrence(&start, false).ok()?;
+        u64::try_from(next.timestamp()).ok()
+    }
+
+    /// Calendar date of `unix` in the schedule's time zone.
+    pub fn local_date(&self, unix: u64) -> Option<NaiveDate> {
+        DateTime::<Utc>::from_timestamp(unix as i64, 0)
+            .map(|t| t.with_timezone(&self.tz).date_naive())
+    }
+}
+
+// --- State ---
+
+/// Persisted progress of one recurring job.
+#[derive(Default, Serialize, Deserialize)]
+pub struct RecurringState {
+    /// `RecurringJob::schedule_key` the times below were computed with.
+    pub schedule: String,
+    pub next_run: Option<u64>,
+    pub last_run: Option<u64>,
+    /// What happened at `last_run`: the queued post ID, a skip reason or an error.
+    pub last_outcome: Option<String>,
+}
+
+/// Persisted rotation of one evergreen queue.
+#[derive(Default, Serialize, Deserialize)]
+pub struct EvergreenState {
+    /// Post fingerprint -> when it was last used. Keyed by content, so
+    /// reordering or editing the config list doesn't lose history.
+    pub last_used: HashMap<String, u64>,
+    /// Fingerprint of the most recent post, where the rotation continues from.
+    pub last_key: Option<String>,
+}
+
+impl EvergreenPost {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Post { text, .. } => text,
+        }
+    }
+
+    pub fn media(&self) -> &[MediaAttachment] {
+        match self {
+            Self::Text(_) => &[],
+            Self::Post { media, .. } => media,
+        }
+    }
+
+    pub fn key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.text().trim().as_bytes());
+        for m in self.media() {
+            hasher.update(b"\0");
+            hasher.update(m.path.as_bytes());
+        }
+        hasher.finalize()[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+impl EvergreenQueue {
+    /// Index of the next post in rotation order that hasn't been used within
+    /// the repeat window, or `None` if every post is still too recent.
+    pub fn next_eligible(&self, state: &EvergreenState, now: u64) -> Option<usize> {
+        let window = self.min_days_between_repeats * 86_400;
+        let start = state
+            .last_key
+            .as_ref()
+            .and_then(|key| self.posts.iter().position(|p| &p.key() == key))
+            .map_or(0, |i| i + 1);
+        (0..self.posts.len())
+            .map(|offset| (start + offset) % self.posts.len())
+            .find(|&i| {
+                state
+                    .last_used
+                    .get(&self.posts[i].key())
+                    .is_none_or(|&used| now.saturating_sub(used) >= window)
+            })
+    }
+
+    /// Number of posts that could go out right now.
+    pub fn eligible_count(&self, state: &EvergreenState, now: u64) -> usize {
+        let window = self.min_days_between_repeats * 86_400;
+        self.posts
+            .iter()
+            .filter(|p| {
+                state
+                    .last_used
+                    .get(&p.key())
+                    .is_none_or(|&used| now.saturating_sub(used) >= window)
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn queue(posts: &[&str], min_days: u64) -> EvergreenQueue {
+        EvergreenQueue {
+            min_days_between_repeats: min_days,
+            posts: posts
+                .iter()
+                .map(|p| EvergreenPost::Text(p.to_string()))
+                .collect(),
+        }
+    }
+
+    /// State after using `indexes` of `queue` in order, one day apart, ending at `end`.
+    fn used(queue: &EvergreenQueue, indexes: &[usize], end: u64) -> EvergreenState {
+        let mut state = EvergreenState::default();
+        let first = end - (indexes.len() as u64 - 1) * DAY;
+        for (n, &i) in indexes.iter().enumerate() {
+            let key = queue.posts[i].key();
+            state.last_used.insert(key.clone(), first + n as u64 * DAY);
+            state.last_key = Some(key);
+        }
+        state
+    }
+
+    fn job(text: Option<&str>, evergreen: Option<&str>) -> RecurringJob {
+        RecurringJob {
+            name: "weekly".into(),
+            account: None,
+            cron: "0 10 * * TUE".into(),
+            timezone: default_timezone(),
+            text: text.map(str::to_string),
+            media: Vec::new(),
+            evergreen: evergreen.map(str::to_string),
+            skip_holidays: true,
+            if_missed: MissedPolicy::default(),
+        }
+    }
+
+    fn accounts() -> HashMap<&'static str, usize> {
+        HashMap::from([("main", 10)])
+    }
+
+    #[test]
+    fn fresh_queue_starts_at_the_first_post() {
+        let q = queue(&["a", "b", "c"], 30);
+        assert_eq!(
+            q.next_eligible(&EvergreenState::default(), 100 * DAY),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn rotation_continues_after_the_last_post() {
+        let q = queue(&["a", "b", "c"], 0);
+        let now = 100 * DAY;
+        assert_eq!(q.next_eligible(&used(&q, &[0], now), now), Some(1));
+        assert_eq!(q.next_eligible(&used(&q, &[0, 1, 2], now), now), Some(0));
+    }
+
+    #[test]
+    fn recently_used_posts_are_skipped_when_wrapping_around() {
+        let q = queue(&["a", "b", "c"], 7);
+        let now = 100 * DAY;
+        // "b" was used long ago, "c" and "a" within the window.
+        let mut state = used(&q, &[2, 0], now);
+        state.last_used.insert(q.posts[1].key(), now - 30 * DAY);
+        assert_eq!(q.next_eligible(&state, now), Some(1));
+        assert_eq!(q.eligible_count(&state, now), 1);
+    }
+
+    #[test]
+    fn nothing_is_eligible_inside_the_repeat_window() {
+        let q = queue(&["a", "b"], 7);
+        let now = 100 * DAY;
+        let state = used(&q, &[0, 1], now);
+        assert_eq!(q.next_eligible(&state, now), None);
+        assert_eq!(q.next_eligible(&state, now + 6 * DAY), Some(0));
+        assert_eq!(q.eligible_count(&state, now + 6 * DAY), 1);
+    }
+
+    #[test]
+    fn rotation_follows_content_when_posts_are_reordered() {
+        let q = queue(&["a", "b", "c"], 0);
+        let now = 100 * DAY;
+        let state = used(&q, &[0], now);
+        let reordered = queue(&["c", "a", "b"], 0);
+        assert_eq!(reordered.next_eligible(&state, now), Some(2));
+    }
+
+    #[test]
+    fn overlong_recurring_text_is_rejected() {
+        let jobs = vec![job(Some("far too long for ten"), None)];
+        let err =
+            RecurringConfig::from_raw(jobs, HashMap::new(), &[], &accounts(), "main").unwrap_err();
+        assert!(
+            err.starts_with("Recurring job 'weekly': Tweet is too long"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn overlong_evergreen_post_is_rejected() {
+        let evergreen =
+            HashMap::from([("tips".to_string(), queue(&["short", "much too long"], 30))]);
+        let jobs = vec![job(None, Some("tips"))];
+        let err = RecurringConfig::from_raw(jobs, evergreen, &[], &accounts(), "main").unwrap_err();
+        assert!(err.starts_with("Evergreen queue 'tips' post 2"), "{err}");
+    }
+}
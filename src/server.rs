@@ -7,30 +7,140 @@ macro_rules! try_tool {
     };
 }
 
+/// Like `try_tool!`, for helpers that return a `CallToolResult` directly.
+macro_rules! try_ok {
+    ($expr:expr) => {
+        match $expr {
+            Ok(v) => v,
+            Err(e) => return e,
+        }
+    };
+}
+
 use crate::api::{
-    AppConfig, DmEventResult, MeData, MediaAttachment, PostResult, SearchTweetResult, UserProfile,
-    UserSummary, XClient,
+    AppConfig, DmEventResult, MeData, MediaAttachment, PostResult, RequestPreview, SearchResult,
+    SearchTweetResult, ThreadResult, TweetMetricsResult, TweetPreview, UserProfile, UserSummary,
+    XClient,
 };
+use crate::audit::AuditLog;
+use crate::dedupe::{DuplicateGuard, PostFingerprint};
+use crate::engagement::{EngagementConfig, MetricsSample, growth_curve};
+use crate::export::{ExportRow, Exporter};
+use crate::history::{HistoryEntry, HistoryFilter, PostHistory, parse_bound};
+use crate::mentions::{MentionChecker, MentionReport};
 use crate::params::{
-    AccountOnlyParams, FollowsLookupParams, GetDmEventsParams, LookupUserParams,
-    PostThreadParams, PostTweetParams, SearchTweetsParams, SendDmParams, TimelineParams,
-    TweetIdParams, UploadMediaParams,
+    AccountOnlyParams, CancelScheduledParams, DeleteTweetParams, DraftIdParams, EditTweetParams,
+    ExportFormat, FollowList, FollowUserParams, FollowerChangesParams, FollowsLookupParams,
+    GetAllFollowsParams, GetDmEventsParams, GetPostHistoryParams, GetTweetParams, HistoryAction,
+    ListDraftsParams, ListRecurringParams, ListScheduledParams, LookupUserParams,
+    MediaAttachmentParam, MissedPolicy, OnFailure, PostFromTemplateParams,
+    PostThreadFromFileParams, PostThreadParams, PostToAccountsParams, PostTweetParams,
+    PublishDraftParams, ResumeThreadParams, SaveDraftParams, ScheduleThreadParams,
+    ScheduleTweetParams, SearchTweetsParams, SendDmParams, SnapshotFollowersParams, ThreadTweet,
+    TimelineParams, TweetIdParams, TweetPerformanceParams, UnrollThreadParams, UpdateDraftParams,
+    UploadMediaParams,
+};
+use crate::recurring::{EvergreenState, RecurringConfig, RecurringJob, RecurringState};
+use crate::snapshots::{
+    FollowSnapshot, SnapshotDiff, SnapshotSchedule, SnapshotStore, SnapshotUser,
 };
+use crate::store::{
+    Draft, DraftContent, FileLock, JsonStore, PostedTweet, ScheduledContent, ScheduledPost,
+    ScheduledStatus, ThreadJob, ThreadJobStatus, ThreadJobTweet, new_id, now_unix, write_atomic,
+};
+use crate::template::TweetTemplate;
+use crate::text;
+use crate::thread_file;
 use reqwest::Client;
 use rmcp::{
-    ErrorData as McpError, ServerHandler, handler::server::tool::ToolRouter,
-    handler::server::wrapper::Parameters, model::*, tool, tool_handler, tool_router,
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::tool::{ToolCallContext, ToolRouter},
+    handler::server::wrapper::Parameters,
+    model::*,
+    service::RequestContext,
+    tool, tool_router,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// How often the schedule worker looks for due posts.
+const SCHEDULE_TICK: Duration = Duration::from_secs(30);
+
+/// Users listed per section of a follower_changes report.
+const SNAPSHOT_LIST_LIMIT: usize = 100;
+
+/// A scheduled post picked up later than this after its time counts as missed.
+const MISSED_GRACE_SECS: u64 = 5 * 60;
+
+/// Tweets written by a search or timeline export when `max_rows` is omitted,
+/// and the most it may be set to.
+const DEFAULT_EXPORT_ROWS: u32 = 1_000;
+const MAX_EXPORT_ROWS: u32 = 10_000;
+
+/// Where a search or timeline export reads its pages from.
+enum TweetPages<'a> {
+    Search {
+        query: &'a str,
+        sort_order: Option<&'a str>,
+    },
+    Timeline {
+        user_id: &'a str,
+        exclude: Option<&'a str>,
+    },
+}
+
+impl TweetPages<'_> {
+    /// Fetch the page at `token`, asking for up to `wanted` tweets.
+    async fn fetch(
+        &self,
+        client: &XClient,
+        wanted: u32,
+        token: Option<&str>,
+    ) -> Result<SearchResult, String> {
+        match *self {
+            TweetPages::Search { query, sort_order } => {
+                let page_size = wanted.clamp(10, 100);
+                client
+                    .search_recent_tweets(query, page_size, sort_order, token)
+                    .await
+            }
+            TweetPages::Timeline { user_id, exclude } => {
+                let page_size = wanted.clamp(1, 100);
+                client
+                    .get_timeline(user_id, page_size, token, exclude)
+                    .await
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PostXServer {
     clients: HashMap<String, Arc<XClient>>,
     default_account: String,
     cached_me: Arc<Mutex<HashMap<String, MeData>>>,
+    thread_jobs: Arc<JsonStore<ThreadJob>>,
+    scheduled: Arc<JsonStore<ScheduledPost>>,
+    data_dir: PathBuf,
+    duplicates: Arc<DuplicateGuard>,
+    templates: Arc<HashMap<String, TweetTemplate>>,
+    mentions: Arc<MentionChecker>,
+    recurring: Arc<RecurringConfig>,
+    recurring_state: Arc<JsonStore<RecurringState>>,
+    evergreen_state: Arc<JsonStore<EvergreenState>>,
+    drafts: Arc<JsonStore<Draft>>,
+    history: Arc<PostHistory>,
+    audit: Option<Arc<AuditLog>>,
+    snapshots: Arc<SnapshotStore>,
+    snapshot_schedule: Option<Arc<SnapshotSchedule>>,
+    /// Progress of `snapshot_schedule`, saved as `schedule.json` beside the snapshots.
+    snapshot_state: Arc<JsonStore<RecurringState>>,
+    engagement: Option<Arc<EngagementConfig>>,
+    /// Progress of the metrics tracker, saved as `<data_dir>/engagement/state.json`.
+    engagement_state: Arc<JsonStore<RecurringState>>,
     instructions: String,
     tool_router: ToolRouter<Self>,
 }
@@ -109,6 +219,25 @@ impl PostXServer {
         }
     }
 
+    fn duplicate_refusal(account: &str, previous: &PostFingerprint) -> CallToolResult {
+        let mins_ago = now_unix().saturating_sub(previous.posted_at) / 60;
+        let text = if previous.pending {
+            format!(
+                "Refused: an identical tweet is already being posted as @{account} \
+                 (started {mins_ago} minute(s) ago).\n\
+                 Pass allow_duplicate: true to post it anyway."
+            )
+        } else {
+            format!(
+                "Refused: an identical tweet was already posted as @{account} \
+                 {mins_ago} minute(s) ago.\nID: {}\nURL: {}\n\
+                 Pass allow_duplicate: true to post it anyway.",
+                previous.tweet_id, previous.url
+            )
+        };
+        CallToolResult::error(vec![Content::text(text)])
+    }
+
     fn ok_or_err(result: Result<String, String>) -> CallToolResult {
         match result {
             Ok(text) => CallToolResult::success(vec![Content::text(text)]),
@@ -116,10 +245,680 @@ impl PostXServer {
         }
     }
 
+    /// All text content of a tool result, joined by newlines.
+    fn result_text(result: &CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     fn format_post_result(result: &PostResult, account: &str) -> String {
-        format!(
+        let mut output = format!(
             "Tweet posted as @{account}!\nID: {}\nURL: {}",
             result.tweet_id, result.url
+        );
+        Self::append_transformations(&mut output, &result.transformations, "");
+        if !result.transformations.is_empty() {
+            output.push_str(&format!("\nPosted text:\n{}", result.text));
+        }
+        output
+    }
+
+    fn append_transformations(output: &mut String, transformations: &[String], indent: &str) {
+        if transformations.is_empty() {
+            return;
+        }
+        output.push_str(&format!("\n{indent}Text changes applied:"));
+        for t in transformations {
+            output.push_str(&format!("\n{indent}  - {t}"));
+        }
+    }
+
+    /// Mention problems that didn't block the post, and handles that couldn't be checked.
+    fn append_mention_warnings(output: &mut String, report: &MentionReport, strict: bool) {
+        if !strict && !report.problems.is_empty() {
+            output.push_str("\nMention warnings:");
+            for problem in &report.problems {
+                output.push_str(&format!("\n  - {problem}"));
+            }
+        }
+        if !report.unverified.is_empty() {
+            output.push_str("\nCould not verify mention(s):");
+            for handle in &report.unverified {
+                output.push_str(&format!("\n  - {handle}"));
+            }
+        }
+    }
+
+    fn append_thread_posts(output: &mut String, posts: &[PostResult], offset: usize) {
+        for (i, post) in posts.iter().enumerate() {
+            output.push_str(&format!(
+                "  {}. ID: {} — {}",
+                offset + i + 1,
+                post.tweet_id,
+                post.url
+            ));
+            Self::append_transformations(output, &post.transformations, "     ");
+            output.push('\n');
+        }
+    }
+
+    /// Point at `resume_thread`, but only once part of the thread is live;
+    /// before that the thread can simply be posted again.
+    fn append_resume_hint(output: &mut String, job: &ThreadJob) {
+        if job.status == ThreadJobStatus::Failed && !job.posted.is_empty() {
+            output.push_str(&format!(
+                "\nThread job: {} — call resume_thread with this job_id to continue \
+                 from tweet {} without re-posting the tweets above.",
+                job.id,
+                job.posted.len() + 1
+            ));
+        }
+    }
+
+    fn append_rollback(output: &mut String, outcomes: &[(String, Result<(), String>)]) {
+        if outcomes.is_empty() {
+            output.push_str("\nRollback: nothing had been posted, nothing to delete.");
+            return;
+        }
+        let deleted = outcomes.iter().filter(|(_, o)| o.is_ok()).count();
+        output.push_str(&format!(
+            "\nRollback: deleted {deleted}/{} posted tweets (newest first):\n",
+            outcomes.len()
+        ));
+        for (id, outcome) in outcomes {
+            match outcome {
+                Ok(()) => output.push_str(&format!("  - {id} deleted\n")),
+                Err(e) => output.push_str(&format!("  - {id} NOT deleted: {e}\n")),
+            }
+        }
+        if deleted < outcomes.len() {
+            output.push_str(
+                "Tweets that were not deleted are still live; delete them with delete_tweet.",
+            );
+        }
+    }
+
+    /// Persist a thread job, logging rather than failing the post if the store is unavailable.
+    fn save_thread_job(&self, job: &mut ThreadJob) {
+        job.updated_at = now_unix();
+        if let Err(e) = self.thread_jobs.save(&job.id, job) {
+            tracing::warn!("Failed to save thread job {}: {e}", job.id);
+        }
+    }
+
+    /// Claim a thread job or draft (`key` is e.g. `thread-<id>`) for posting, or
+    /// `Ok(None)` if another call holds it. The claim is released when dropped,
+    /// or by the OS if the process dies while posting.
+    fn claim(&self, key: &str) -> Result<Option<FileLock>, String> {
+        FileLock::try_acquire(&self.data_dir.join("locks").join(format!("{key}.lock")))
+    }
+
+    fn finish_thread_job(job: &mut ThreadJob, result: &ThreadResult) {
+        match &result.error {
+            Some(err) => {
+                job.status = ThreadJobStatus::Failed;
+                job.failed_index = result.failed_index;
+                job.error = Some(err.clone());
+            }
+            None => job.status = ThreadJobStatus::Completed,
+        }
+    }
+
+    /// Add an entry to the post history, logging rather than failing if it can't be written.
+    fn record_history(&self, entry: HistoryEntry) {
+        if let Err(e) = self.history.record(&entry) {
+            tracing::warn!("Failed to record {} in post history: {e}", entry.tweet_id);
+        }
+    }
+
+    /// Record tweet `index` of a thread job, just after it was posted.
+    fn record_thread_post(&self, job: &ThreadJob, index: usize, post: &PostResult) {
+        let mut entry = HistoryEntry::new(HistoryAction::Thread, &job.account, &post.tweet_id);
+        entry.url = Some(post.url.clone());
+        entry.text = Some(post.text.clone());
+        if let Some(tweet) = job.tweets.get(index) {
+            entry.media = tweet.media.iter().map(|m| m.path.clone()).collect();
+        }
+        entry.reply_to = if index == 0 {
+            job.reply_to.clone()
+        } else {
+            job.posted.get(index - 1).map(|p| p.tweet_id.clone())
+        };
+        entry.thread_id = job.posted.first().map(|p| p.tweet_id.clone());
+        self.record_history(entry);
+    }
+
+    /// Post a thread as a persisted job, saving progress after every tweet so a
+    /// failure can be picked up by `resume_thread`.
+    async fn post_thread_job(
+        &self,
+        account: &str,
+        client: &XClient,
+        username: &str,
+        tweets: &[(String, Vec<MediaAttachment>)],
+        reply_to: Option<&str>,
+    ) -> (ThreadJob, ThreadResult, Option<FileLock>) {
+        let now = now_unix();
+        let mut job = ThreadJob {
+            id: new_id("thread"),
+            account: account.to_string(),
+            tweets: tweets
+                .iter()
+                .map(|(text, media)| ThreadJobTweet {
+                    text: text.clone(),
+                    media: media.clone(),
+                })
+                .collect(),
+            reply_to: reply_to.map(str::to_string),
+            posted: Vec::new(),
+            status: ThreadJobStatus::InProgress,
+            failed_index: None,
+            error: None,
+            rolled_back: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        // A fresh id can't be held by anyone else; the claim only keeps
+        // `resume_thread` off the job while this call is still posting it.
+        let claim = self.claim(&format!("thread-{}", job.id)).unwrap_or_else(|e| {
+            tracing::warn!("Failed to claim thread job {}: {e}", job.id);
+            None
+        });
+        self.save_thread_job(&mut job);
+
+        let result = client
+            .post_thread(tweets, reply_to, username, |i, post| {
+                job.posted.push(PostedTweet {
+                    tweet_id: post.tweet_id.clone(),
+                    url: post.url.clone(),
+                });
+                self.save_thread_job(&mut job);
+                self.record_thread_post(&job, i, post);
+            })
+            .await;
+        Self::finish_thread_job(&mut job, &result);
+        self.save_thread_job(&mut job);
+        (job, result, claim)
+    }
+
+    /// Post (or preview) a thread and persist it as a resumable job. Shared by
+    /// `post_thread`, `post_thread_from_file` and the `thread` CLI command.
+    async fn run_thread(
+        &self,
+        account: Option<&str>,
+        tweets: Vec<(String, Vec<MediaAttachment>)>,
+        reply_to: Option<&str>,
+        on_failure: OnFailure,
+        dry_run: bool,
+    ) -> CallToolResult {
+        let reply_to = match reply_to {
+            Some(raw) => Some(try_ok!(Self::require_tweet_id(raw))),
+            None => None,
+        };
+
+        let (account, client, me) = try_ok!(self.require_me_for(account).await);
+
+        if dry_run {
+            let previews = client.preview_thread(&tweets, reply_to);
+            return Self::ok_or_err(previews.map(|previews| {
+                let mut output = Self::dry_run_header(&account);
+                for (i, p) in previews.iter().enumerate() {
+                    output.push_str(&format!("--- Tweet {} of {} ---\n", i + 1, previews.len()));
+                    Self::format_tweet_preview(&mut output, p);
+                    output.push('\n');
+                }
+                output
+            }));
+        }
+
+        // Held through any rollback below.
+        let (mut job, result, _claim) = self
+            .post_thread_job(&account, &client, &me.username, &tweets, reply_to)
+            .await;
+
+        let mut output = String::new();
+        if !result.posted.is_empty() {
+            output.push_str(&format!(
+                "Posted {}/{} tweets as @{account}:\n",
+                result.posted.len(),
+                job.tweets.len()
+            ));
+            Self::append_thread_posts(&mut output, &result.posted, 0);
+        }
+
+        if let Some(err) = &result.error {
+            output.push_str(&format!("\nError: {err}"));
+            if on_failure == OnFailure::Rollback {
+                let outcomes = client.rollback_thread(&result.posted).await;
+                Self::append_rollback(&mut output, &outcomes);
+                let (deleted, kept): (Vec<_>, Vec<_>) =
+                    outcomes.into_iter().partition(|(_, outcome)| outcome.is_ok());
+                job.rolled_back = deleted.into_iter().map(|(id, _)| id).collect();
+                if kept.is_empty() {
+                    job.status = ThreadJobStatus::RolledBack;
+                } else {
+                    // Still live, so the job stays failed and says which tweets to clean up.
+                    let kept: Vec<String> = kept.into_iter().map(|(id, _)| id).collect();
+                    job.error = Some(format!(
+                        "{err}; rollback failed to delete {}",
+                        kept.join(", ")
+                    ));
+                }
+                self.save_thread_job(&mut job);
+                for id in &job.rolled_back {
+                    self.record_history(HistoryEntry::new(HistoryAction::Delete, &account, id));
+                }
+            } else {
+                Self::append_resume_hint(&mut output, &job);
+            }
+            return CallToolResult::error(vec![Content::text(output)]);
+        }
+
+        CallToolResult::success(vec![Content::text(output)])
+    }
+
+    /// Parse a Markdown thread file and post it. Parameters override front-matter.
+    pub async fn post_thread_file(&self, params: PostThreadFromFileParams) -> CallToolResult {
+        let file = match thread_file::load(Path::new(&params.path)) {
+            Ok(file) => file,
+            Err(e) => return CallToolResult::error(vec![Content::text(e)]),
+        };
+        if file.tweets.len() > 25 {
+            return CallToolResult::error(vec![Content::text(format!(
+                "{} contains {} tweets; a thread cannot exceed 25 tweets",
+                params.path,
+                file.tweets.len()
+            ))]);
+        }
+        let account = params.account.or(file.account);
+        let reply_to = params.reply_to.or(file.reply_to);
+        self.run_thread(
+            account.as_deref(),
+            file.tweets,
+            reply_to.as_deref(),
+            params.on_failure.unwrap_or_default(),
+            params.dry_run == Some(true),
+        )
+        .await
+    }
+
+    /// Template names and their placeholders, for the server instructions.
+    fn describe_templates(templates: &HashMap<String, TweetTemplate>) -> String {
+        if templates.is_empty() {
+            return String::new();
+        }
+        let mut names: Vec<&String> = templates.keys().collect();
+        names.sort();
+        let described: Vec<String> = names
+            .into_iter()
+            .map(|name| {
+                let placeholders = templates[name].placeholders().unwrap_or_default();
+                format!("{name}({})", placeholders.join(", "))
+            })
+            .collect();
+        format!(
+            " Templates for post_from_template: {}.",
+            described.join(", ")
+        )
+    }
+
+    /// Parse an RFC 3339 `post_at`, rejecting times already in the past.
+    fn parse_post_at(raw: &str) -> Result<u64, CallToolResult> {
+        let parsed = chrono::DateTime::parse_from_rfc3339(raw.trim()).map_err(|e| {
+            CallToolResult::error(vec![Content::text(format!(
+                "Invalid post_at '{raw}': {e}. Use RFC 3339 with an offset, \
+                 e.g. 2025-06-01T09:00:00+02:00"
+            ))])
+        })?;
+        let post_at = u64::try_from(parsed.timestamp()).unwrap_or(0);
+        if post_at + 60 < now_unix() {
+            return Err(CallToolResult::error(vec![Content::text(format!(
+                "post_at {raw} is in the past"
+            ))]));
+        }
+        Ok(post_at)
+    }
+
+    /// Every stored sample of one tweet, for `tweet_performance`.
+    fn tweet_performance_detail(&self, tweet_id: &str, now: u64) -> Result<String, String> {
+        let post = self.history.find_post(tweet_id)?.ok_or_else(|| {
+            format!("Tweet {tweet_id} isn't in the post history, so its metrics aren't tracked.")
+        })?;
+        let samples = self.history.metrics_for(tweet_id)?;
+        let mut output = format!(
+            "Performance of {} ({}), posted {} ({})\n",
+            post.tweet_id,
+            post.account,
+            Self::format_unix(post.created_at),
+            Self::format_relative(post.created_at, now)
+        );
+        if let Some(text) = &post.text {
+            output.push_str(&format!("\"{}\"\n", Self::truncate_str(text, 120)));
+        }
+        if samples.is_empty() {
+            output.push_str(
+                "\nNo metrics stored yet. Pass refresh=true, or add an [engagement] section to \
+                 the config.",
+            );
+            return Ok(output);
+        }
+
+        output.push_str(&format!("\nSamples ({}):\n", samples.len()));
+        for s in &samples {
+            let mut counts = format!(
+                "{} likes, {} retweets, {} replies, {} quotes",
+                s.likes, s.retweets, s.replies, s.quotes
+            );
+            for (label, value) in [
+                ("bookmarks", s.bookmarks),
+                ("link clicks", s.url_clicks),
+                ("profile clicks", s.profile_clicks),
+                ("organic impressions", s.organic_impressions),
+            ] {
+                if let Some(value) = value {
+                    counts.push_str(&format!(", {value} {label}"));
+                }
+            }
+            output.push_str(&format!(
+                "- {} | {} impressions | {} engagements ({counts}) | {}\n",
+                Self::format_age(s.fetched_at.saturating_sub(post.created_at)),
+                s.impressions.map_or("?".to_string(), |i| i.to_string()),
+                s.engagements(),
+                Self::format_rate(s.engagement_rate())
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Time since posting, e.g. "+45m", "+6h" or "+2d 3h".
+    fn format_age(secs: u64) -> String {
+        let mins = secs / 60;
+        if mins < 60 {
+            format!("+{mins}m")
+        } else if mins < 48 * 60 {
+            format!("+{}h", mins / 60)
+        } else {
+            format!("+{}d {}h", mins / 1440, mins % 1440 / 60)
+        }
+    }
+
+    fn format_rate(rate: Option<f64>) -> String {
+        rate.map_or("n/a".to_string(), |r| format!("{:.2}%", r * 100.0))
+    }
+
+    /// A "Label (n):" section of snapshot users, capped at `SNAPSHOT_LIST_LIMIT`.
+    fn push_snapshot_users(output: &mut String, label: &str, users: &[SnapshotUser]) {
+        if users.is_empty() {
+            return;
+        }
+        output.push_str(&format!("\n{label} ({}):\n", users.len()));
+        for user in users.iter().take(SNAPSHOT_LIST_LIMIT) {
+            output.push_str(&format!("  - @{} ({})\n", user.username, user.name));
+        }
+        if users.len() > SNAPSHOT_LIST_LIMIT {
+            output.push_str(&format!(
+                "  ... and {} more\n",
+                users.len() - SNAPSHOT_LIST_LIMIT
+            ));
+        }
+    }
+
+    fn format_unix(ts: u64) -> String {
+        chrono::DateTime::from_timestamp(ts as i64, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| ts.to_string())
+    }
+
+    /// "in 2h 5m" or "12m ago", relative to `now`.
+    fn format_relative(ts: u64, now: u64) -> String {
+        let mins = ts.abs_diff(now) / 60;
+        let span = if mins >= 60 {
+            format!("{}h {}m", mins / 60, mins % 60)
+        } else {
+            format!("{mins}m")
+        };
+        if ts >= now {
+            format!("in {span}")
+        } else {
+            format!("{span} ago")
+        }
+    }
+
+    /// The snake_case name a status is stored under, e.g. "pending".
+    fn status_name(status: impl serde::Serialize) -> String {
+        serde_json::to_value(status)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    fn format_scheduled(job: &ScheduledPost, now: u64) -> String {
+        let status = Self::status_name(job.status);
+        let summary = match &job.content {
+            ScheduledContent::Tweet { text, .. } => {
+                format!("tweet: \"{}\"", Self::truncate_str(text, 60))
+            }
+            ScheduledContent::Thread { tweets, .. } => {
+                let first = tweets.first().map(|t| t.text.as_str()).unwrap_or_default();
+                format!(
+                    "thread of {}: \"{}\"",
+                    tweets.len(),
+                    Self::truncate_str(first, 60)
+                )
+            }
+        };
+        let mut output = format!(
+            "- {} | {} ({}) | {status} | {} | {summary}\n",
+            job.id,
+            Self::format_unix(job.post_at),
+            Self::format_relative(job.post_at, now),
+            job.account
+        );
+        if let Some(first) = job.posted.first() {
+            output.push_str(&format!("    Posted: {}\n", first.url));
+        }
+        if let Some(err) = &job.error {
+            output.push_str(&format!("    Error: {err}\n"));
+        }
+        if let Some(thread_job) = &job.thread_job_id
+            && job.status == ScheduledStatus::Failed
+        {
+            output.push_str(&format!("    Thread job: {thread_job} (use resume_thread)\n"));
+        }
+        output
+    }
+
+    fn save_scheduled(&self, job: &mut ScheduledPost) {
+        job.updated_at = now_unix();
+        if let Err(e) = self.scheduled.save(&job.id, job) {
+            tracing::warn!("Failed to save scheduled post {}: {e}", job.id);
+        }
+    }
+
+    /// Store a new scheduled post and describe it for the tool result.
+    fn enqueue_scheduled(&self, job: ScheduledPost, what: &str) -> CallToolResult {
+        if let Err(e) = self.scheduled.save(&job.id, &job) {
+            return CallToolResult::error(vec![Content::text(format!(
+                "Failed to save scheduled post: {e}"
+            ))]);
+        }
+        CallToolResult::success(vec![Content::text(format!(
+            "Scheduled {what} as {} for {} ({}).\nJob ID: {}\n\
+             It is posted by this server or a running `post-x daemon`.",
+            job.account,
+            Self::format_unix(job.post_at),
+            Self::format_relative(job.post_at, now_unix()),
+            job.id
+        ))])
+    }
+
+    fn draft_tweets(tweets: Vec<ThreadTweet>) -> Result<Vec<ThreadJobTweet>, CallToolResult> {
+        if tweets.is_empty() || tweets.len() > 25 {
+            return Err(CallToolResult::error(vec![Content::text(
+                "A thread draft must have between 1 and 25 tweets.",
+            )]));
+        }
+        Ok(tweets
+            .into_iter()
+            .map(|t| ThreadJobTweet {
+                text: t.text,
+                media: t
+                    .media
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Why the draft couldn't be published as it stands, if anything.
+    fn draft_problem(client: &XClient, draft: &Draft) -> Option<String> {
+        let checked = match &draft.content {
+            DraftContent::Tweet {
+                text,
+                media,
+                quote_tweet_id,
+            } => client
+                .preview_tweet(
+                    text,
+                    media,
+                    None,
+                    draft.reply_to.as_deref(),
+                    quote_tweet_id.as_deref(),
+                )
+                .map(|_| ()),
+            DraftContent::Thread { tweets } => {
+                let tweets: Vec<(String, Vec<MediaAttachment>)> = tweets
+                    .iter()
+                    .map(|t| (t.text.clone(), t.media.clone()))
+                    .collect();
+                client
+                    .preview_thread(&tweets, draft.reply_to.as_deref())
+                    .map(|_| ())
+            }
+        };
+        checked.err()
+    }
+
+    /// Save a draft and describe it, warning if it couldn't be published yet.
+    fn store_draft(&self, draft: &Draft, client: &XClient, verb: &str) -> CallToolResult {
+        if let Err(e) = self.drafts.save(&draft.id, draft) {
+            return CallToolResult::error(vec![Content::text(format!(
+                "Failed to save draft: {e}"
+            ))]);
+        }
+        let mut output = format!("{verb} draft {}.\n", draft.id);
+        if let Some(problem) = Self::draft_problem(client, draft) {
+            output.push_str(&format!(
+                "Warning: it can't be published as it stands: {problem}\n"
+            ));
+        }
+        output.push('\n');
+        output.push_str(&Self::format_draft(draft, now_unix()));
+        CallToolResult::success(vec![Content::text(output)])
+    }
+
+    fn format_draft(draft: &Draft, now: u64) -> String {
+        let kind = match &draft.content {
+            DraftContent::Tweet { .. } => "tweet".to_string(),
+            DraftContent::Thread { tweets } => format!("thread of {}", tweets.len()),
+        };
+        let updated = if now.saturating_sub(draft.updated_at) < 60 {
+            "just now".to_string()
+        } else {
+            Self::format_relative(draft.updated_at, now)
+        };
+        let mut output = format!(
+            "- {} | {} | {kind} | updated {updated}\n",
+            draft.id, draft.account
+        );
+        if let Some(note) = &draft.note {
+            output.push_str(&format!("    Note: {note}\n"));
+        }
+        if let Some(reply_to) = &draft.reply_to {
+            output.push_str(&format!("    Reply to: {reply_to}\n"));
+        }
+        match &draft.content {
+            DraftContent::Tweet {
+                text,
+                media,
+                quote_tweet_id,
+            } => {
+                if let Some(quote) = quote_tweet_id {
+                    output.push_str(&format!("    Quote: {quote}\n"));
+                }
+                Self::append_draft_tweet(&mut output, "Text", text, media);
+            }
+            DraftContent::Thread { tweets } => {
+                for (i, tweet) in tweets.iter().enumerate() {
+                    let label = format!("Tweet {}/{}", i + 1, tweets.len());
+                    Self::append_draft_tweet(&mut output, &label, &tweet.text, &tweet.media);
+                }
+            }
+        }
+        output
+    }
+
+    fn append_draft_tweet(output: &mut String, label: &str, text: &str, media: &[MediaAttachment]) {
+        output.push_str(&format!("    {label}:\n"));
+        for line in text.lines() {
+            output.push_str(&format!("      {line}\n"));
+        }
+        for m in media {
+            output.push_str(&format!("      [media] {}", m.path));
+            if let Some(alt) = &m.alt_text {
+                output.push_str(&format!(" (alt: {alt})"));
+            }
+            output.push('\n');
+        }
+    }
+
+    /// `Some(None)` for an empty string, so update params can clear a field.
+    fn clearable(value: Option<String>) -> Option<Option<String>> {
+        value.map(|v| Some(v).filter(|v| !v.trim().is_empty()))
+    }
+
+    /// Like `clearable`, for tweet ID or URL fields.
+    fn clearable_tweet_id(
+        value: Option<String>,
+    ) -> Result<Option<Option<String>>, CallToolResult> {
+        match Self::clearable(value) {
+            Some(Some(raw)) => Ok(Some(Some(Self::require_tweet_id(&raw)?.to_string()))),
+            other => Ok(other),
+        }
+    }
+
+    fn format_request_preview(request: &RequestPreview) -> String {
+        let mut output = format!("{} {}", request.method, request.url);
+        if !request.body.is_null() {
+            let body = serde_json::to_string_pretty(&request.body).unwrap_or_default();
+            output.push_str(&format!("\n{body}"));
+        }
+        output
+    }
+
+    fn format_tweet_preview(output: &mut String, preview: &TweetPreview) {
+        for upload in &preview.uploads {
+            output.push_str(&format!("Upload media: {upload}\n"));
+        }
+        output.push_str(&Self::format_request_preview(&preview.request));
+        output.push_str(&format!(
+            "\nWeighted length: {}/{}",
+            preview.weighted_length, preview.max_length
+        ));
+        Self::append_transformations(output, &preview.transformations, "");
+        output.push('\n');
+    }
+
+    fn dry_run_header(account: &str) -> String {
+        format!(
+            "Dry run (account: {account}) — nothing was sent to X. \
+             Request(s) that would be sent:\n\n"
         )
     }
 
@@ -153,15 +952,15 @@ impl PostXServer {
                 user.name,
                 followers_str,
             ));
-            if let Some(ref desc) = user.description {
-                if !desc.is_empty() {
-                    let truncated = if desc.len() > 100 {
-                        format!("{}...", Self::truncate_str(desc, 97))
-                    } else {
-                        desc.clone()
-                    };
-                    output.push_str(&format!("     {truncated}\n"));
-                }
+            if let Some(ref desc) = user.description
+                && !desc.is_empty()
+            {
+                let truncated = if desc.len() > 100 {
+                    format!("{}...", Self::truncate_str(desc, 97))
+                } else {
+                    desc.clone()
+                };
+                output.push_str(&format!("     {truncated}\n"));
             }
         }
 
@@ -188,15 +987,15 @@ impl PostXServer {
                 user.name,
                 followers_str,
             ));
-            if let Some(ref desc) = user.description {
-                if !desc.is_empty() {
-                    let truncated = if desc.len() > 100 {
-                        format!("{}...", Self::truncate_str(desc, 97))
-                    } else {
-                        desc.clone()
-                    };
-                    output.push_str(&format!("     {truncated}\n"));
-                }
+            if let Some(ref desc) = user.description
+                && !desc.is_empty()
+            {
+                let truncated = if desc.len() > 100 {
+                    format!("{}...", Self::truncate_str(desc, 97))
+                } else {
+                    desc.clone()
+                };
+                output.push_str(&format!("     {truncated}\n"));
             }
         }
 
@@ -207,20 +1006,20 @@ impl PostXServer {
         let mut output = format!("@{} ({})\n", p.username, p.name);
         output.push_str(&format!("  ID: {}\n", p.id));
 
-        if let Some(desc) = &p.description {
-            if !desc.is_empty() {
-                output.push_str(&format!("  Bio: {desc}\n"));
-            }
+        if let Some(desc) = &p.description
+            && !desc.is_empty()
+        {
+            output.push_str(&format!("  Bio: {desc}\n"));
         }
-        if let Some(loc) = &p.location {
-            if !loc.is_empty() {
-                output.push_str(&format!("  Location: {loc}\n"));
-            }
+        if let Some(loc) = &p.location
+            && !loc.is_empty()
+        {
+            output.push_str(&format!("  Location: {loc}\n"));
         }
-        if let Some(url) = &p.url {
-            if !url.is_empty() {
-                output.push_str(&format!("  URL: {url}\n"));
-            }
+        if let Some(url) = &p.url
+            && !url.is_empty()
+        {
+            output.push_str(&format!("  URL: {url}\n"));
         }
         if let Some(metrics) = &p.public_metrics {
             output.push_str(&format!(
@@ -289,11 +1088,278 @@ impl PostXServer {
         output
     }
 
-    fn format_dm_events(events: &[DmEventResult], next_token: &Option<String>) -> String {
-        if events.is_empty() {
-            return "No DM events found.".to_string();
-        }
-
+    fn format_tweet_details(tweets: &[SearchTweetResult], errors: &[String]) -> String {
+        let mut output = if tweets.is_empty() {
+            "No tweets found.\n".to_string()
+        } else {
+            format!("Found {} tweet(s):\n", tweets.len())
+        };
+
+        for (i, t) in tweets.iter().enumerate() {
+            let author = match (&t.username, &t.author_name) {
+                (Some(u), Some(n)) => format!("@{u} ({n})"),
+                (Some(u), None) => format!("@{u}"),
+                _ => t
+                    .author_id
+                    .as_deref()
+                    .map(|id| format!("user {id}"))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            };
+            let date = t.created_at.as_deref().unwrap_or("");
+            output.push_str(&format!("\n{}. {} · {}\n", i + 1, author, date));
+            output.push_str(&format!("   {}\n", t.text.replace('\n', "\n   ")));
+            output.push_str(&format!(
+                "   RT:{} Like:{} Reply:{} Quote:{} id:{}\n",
+                t.retweet_count, t.like_count, t.reply_count, t.quote_count, t.id
+            ));
+            if let Some(conv) = &t.conversation_id {
+                output.push_str(&format!("   Conversation: {conv}\n"));
+            }
+            for r in &t.referenced_tweets {
+                let label = match r.kind.as_str() {
+                    "replied_to" => "Replying to",
+                    "quoted" => "Quoting",
+                    "retweeted" => "Retweet of",
+                    other => other,
+                };
+                let who = r
+                    .username
+                    .as_deref()
+                    .map(|u| format!("@{u} "))
+                    .unwrap_or_default();
+                output.push_str(&format!("   {label}: {who}(id:{})\n", r.id));
+                if let Some(text) = &r.text {
+                    let snippet = if text.len() > 200 {
+                        format!("{}...", Self::truncate_str(text, 197))
+                    } else {
+                        text.clone()
+                    };
+                    output.push_str(&format!("     > {}\n", snippet.replace('\n', "\n     > ")));
+                }
+            }
+            for m in &t.media {
+                let url = m.url.as_deref().unwrap_or("(no URL)");
+                output.push_str(&format!("   Media: {} {url}\n", m.media_type));
+                if let Some(alt) = &m.alt_text {
+                    output.push_str(&format!("     Alt: {alt}\n"));
+                }
+            }
+            if !t.urls.is_empty() {
+                let links: Vec<&str> = t.urls.iter().map(|u| u.expanded_url.as_str()).collect();
+                output.push_str(&format!("   Links: {}\n", links.join(" ")));
+            }
+            if !t.hashtags.is_empty() {
+                let tags: Vec<String> = t.hashtags.iter().map(|h| format!("#{h}")).collect();
+                output.push_str(&format!("   Hashtags: {}\n", tags.join(" ")));
+            }
+            if !t.mentions.is_empty() {
+                let mentions: Vec<String> = t.mentions.iter().map(|m| format!("@{m}")).collect();
+                output.push_str(&format!("   Mentions: {}\n", mentions.join(" ")));
+            }
+        }
+
+        if !errors.is_empty() {
+            output.push_str("\nNot returned:\n");
+            for e in errors {
+                output.push_str(&format!("  - {e}\n"));
+            }
+        }
+
+        output
+    }
+
+    fn format_tweet_line(t: &SearchTweetResult) -> String {
+        let author = t
+            .username
+            .as_deref()
+            .map(|u| format!("@{u}"))
+            .unwrap_or_else(|| "unknown".to_string());
+        let date = t
+            .created_at
+            .as_deref()
+            .and_then(|d| d.split('T').next())
+            .unwrap_or("");
+        format!(
+            "{author} · {date} · RT:{} Like:{} Reply:{} Quote:{} id:{}",
+            t.retweet_count, t.like_count, t.reply_count, t.quote_count, t.id
+        )
+    }
+
+    fn replied_to_id(t: &SearchTweetResult) -> Option<&str> {
+        t.referenced_tweets
+            .iter()
+            .find(|r| r.kind == "replied_to")
+            .map(|r| r.id.as_str())
+    }
+
+    /// Render a conversation as an indented reply tree. Replies whose parent is
+    /// missing (older than the search window, deleted, protected) are listed
+    /// separately so nothing is dropped.
+    fn format_conversation(
+        conversation_id: &str,
+        root: Option<&SearchTweetResult>,
+        replies: &[SearchTweetResult],
+        truncated: bool,
+    ) -> String {
+        let root_id = root.map(|r| r.id.as_str()).unwrap_or(conversation_id);
+        let known: HashSet<&str> = replies.iter().map(|t| t.id.as_str()).collect();
+
+        let mut children: HashMap<&str, Vec<&SearchTweetResult>> = HashMap::new();
+        let mut orphans: Vec<&SearchTweetResult> = Vec::new();
+        for t in replies.iter().filter(|t| t.id != root_id) {
+            match Self::replied_to_id(t) {
+                Some(parent) if parent == root_id || known.contains(parent) => {
+                    children.entry(parent).or_default().push(t)
+                }
+                _ => orphans.push(t),
+            }
+        }
+        for list in children.values_mut() {
+            list.sort_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+        }
+        orphans.sort_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+
+        let reply_count = replies.iter().filter(|t| t.id != root_id).count();
+        let mut output = format!("Conversation {conversation_id} ({reply_count} replies):\n\n");
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        match root {
+            Some(r) => Self::render_tree_node(&mut output, r, 0, &children, &mut visited),
+            None => output.push_str(&format!(
+                "[root tweet {conversation_id} unavailable]\n"
+            )),
+        }
+        visited.insert(root_id);
+        if root.is_none()
+            && let Some(top) = children.get(root_id)
+        {
+            for child in top {
+                Self::render_tree_node(&mut output, child, 1, &children, &mut visited);
+            }
+        }
+
+        if !orphans.is_empty() {
+            output.push_str("\nReplies whose parent tweet is unavailable:\n");
+            for t in orphans {
+                Self::render_tree_node(&mut output, t, 1, &children, &mut visited);
+            }
+        }
+
+        if truncated {
+            output.push_str("\nNote: reply limit reached; the conversation may have more replies.");
+        }
+        output.push_str("\nNote: replies come from recent search and only cover the last 7 days.");
+        output
+    }
+
+    fn render_tree_node<'a>(
+        output: &mut String,
+        tweet: &'a SearchTweetResult,
+        depth: usize,
+        children: &HashMap<&str, Vec<&'a SearchTweetResult>>,
+        visited: &mut HashSet<&'a str>,
+    ) {
+        if !visited.insert(tweet.id.as_str()) {
+            return;
+        }
+        let indent = "  ".repeat(depth);
+        output.push_str(&format!("{indent}- {}\n", Self::format_tweet_line(tweet)));
+        output.push_str(&format!(
+            "{indent}  {}\n",
+            tweet.text.replace('\n', &format!("\n{indent}  "))
+        ));
+        if let Some(list) = children.get(tweet.id.as_str()) {
+            for child in list {
+                Self::render_tree_node(output, child, depth + 1, children, visited);
+            }
+        }
+    }
+
+    /// Follow the author's chain of self-replies starting at `root`, picking the
+    /// earliest self-reply at each step.
+    fn collect_self_thread<'a>(
+        root: &'a SearchTweetResult,
+        candidates: &'a [SearchTweetResult],
+    ) -> Vec<&'a SearchTweetResult> {
+        let mut chain = vec![root];
+        let mut current = root.id.as_str();
+        loop {
+            let next = candidates
+                .iter()
+                .filter(|t| t.author_id == root.author_id)
+                .filter(|t| Self::replied_to_id(t) == Some(current))
+                .min_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+            match next {
+                Some(t) if !chain.iter().any(|c| c.id == t.id) => {
+                    current = t.id.as_str();
+                    chain.push(t);
+                }
+                _ => break,
+            }
+        }
+        chain
+    }
+
+    /// Tweet text with t.co links expanded, media links dropped and HTML entities decoded.
+    fn clean_tweet_text(t: &SearchTweetResult) -> String {
+        let mut text = t.text.clone();
+        for u in &t.urls {
+            let is_media_link = !t.media.is_empty()
+                && (u.expanded_url.contains("/photo/") || u.expanded_url.contains("/video/"));
+            let replacement = if is_media_link { "" } else { u.expanded_url.as_str() };
+            text = text.replace(&u.short_url, replacement);
+        }
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+            .trim()
+            .to_string()
+    }
+
+    fn format_thread_markdown(chain: &[&SearchTweetResult]) -> String {
+        let first = chain[0];
+        let username = first.username.as_deref().unwrap_or("unknown");
+        let mut output = match &first.author_name {
+            Some(name) => format!("# Thread by {name} (@{username})\n\n"),
+            None => format!("# Thread by @{username}\n\n"),
+        };
+        let date = first
+            .created_at
+            .as_deref()
+            .and_then(|d| d.split('T').next())
+            .unwrap_or("");
+        output.push_str(&format!(
+            "Source: https://x.com/{username}/status/{} · {date} · {} tweets\n",
+            first.id,
+            chain.len()
+        ));
+
+        for t in chain {
+            output.push_str("\n---\n\n");
+            output.push_str(&Self::clean_tweet_text(t));
+            output.push('\n');
+            for m in &t.media {
+                let Some(url) = &m.url else { continue };
+                if m.media_type == "photo" {
+                    let alt = m.alt_text.as_deref().unwrap_or("image");
+                    output.push_str(&format!("\n![{alt}]({url})\n"));
+                } else {
+                    output.push_str(&format!(
+                        "\n[![{}]({url})](https://x.com/{username}/status/{})\n",
+                        m.media_type, t.id
+                    ));
+                }
+            }
+        }
+
+        output
+    }
+
+    fn format_dm_events(events: &[DmEventResult], next_token: &Option<String>) -> String {
+        if events.is_empty() {
+            return "No DM events found.".to_string();
+        }
+
         let mut output = format!("DM events ({} messages):\n", events.len());
         for (i, e) in events.iter().enumerate() {
             let sender = e.sender_id.as_deref().unwrap_or("unknown");
@@ -323,6 +1389,138 @@ impl PostXServer {
             output.push_str(&format!("\nMore results available. Next page token: {token}"));
         }
     }
+
+    /// Where a tool writes a local file. Relative paths are resolved under
+    /// `<data_dir>/<subdir>`, so an agent can't scatter files through the working
+    /// directory; absolute paths must already point inside `data_dir`.
+    fn resolve_output_path(&self, path: &str, subdir: &str) -> Result<PathBuf, String> {
+        let raw = Path::new(path);
+        if raw
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            return Err(format!(
+                "Output paths may not contain '..': {}",
+                raw.display()
+            ));
+        }
+        let path = if raw.is_absolute() {
+            raw.to_path_buf()
+        } else {
+            self.data_dir.join(subdir).join(raw)
+        };
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(format!("Not a file path: {}", raw.display()));
+        };
+        if !raw.is_absolute() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        // Compare canonical paths so a symlink can't lead out of data_dir.
+        let canonical = |p: &Path| {
+            p.canonicalize()
+                .map_err(|e| format!("Failed to resolve {}: {e}", p.display()))
+        };
+        let root = canonical(&self.data_dir)?;
+        let dir = canonical(dir)?;
+        if !dir.starts_with(&root) {
+            return Err(format!(
+                "Output paths must be inside the data directory ({}): {}",
+                self.data_dir.display(),
+                raw.display()
+            ));
+        }
+        Ok(dir.join(name))
+    }
+
+    /// Open an export file, with relative paths under `<data_dir>/exports`.
+    fn create_exporter<T: ExportRow>(
+        &self,
+        path: &str,
+        format: Option<ExportFormat>,
+        overwrite: Option<bool>,
+    ) -> Result<Exporter<T>, String> {
+        let path = self.resolve_output_path(path, "exports")?;
+        Exporter::create(path, format, overwrite == Some(true))
+    }
+
+    /// Stream pages of tweets into an export file, starting at `token`, until
+    /// the results run out or `max_rows` tweets are written.
+    async fn export_tweet_pages(
+        client: &XClient,
+        source: TweetPages<'_>,
+        mut exporter: Exporter<SearchTweetResult>,
+        max_rows: Option<u32>,
+        mut token: Option<String>,
+    ) -> Result<String, String> {
+        let max_rows = max_rows
+            .unwrap_or(DEFAULT_EXPORT_ROWS)
+            .clamp(1, MAX_EXPORT_ROWS);
+        let mut written = 0u32;
+        let mut page = 0u32;
+        let cut_short = loop {
+            page += 1;
+            let result = source
+                .fetch(client, max_rows - written, token.as_deref())
+                .await?;
+            let wanted = ((max_rows - written) as usize).min(result.tweets.len());
+            exporter.write_all(&result.tweets[..wanted])?;
+            written += wanted as u32;
+            tracing::info!("export: page {page} wrote {wanted} tweets, {written} so far");
+            token = result.next_token;
+            if token.is_none() || written >= max_rows || result.tweets.is_empty() {
+                break wanted < result.tweets.len();
+            }
+            // Small delay to be respectful of rate limits
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+
+        let mut output = Self::finish_export(exporter, "tweets")?;
+        if token.is_some() {
+            output.push_str(&format!("\nStopped at max_rows ({max_rows})."));
+            if cut_short {
+                output.push_str(" The last page was cut short, so no resume token is given.");
+            } else {
+                Self::append_pagination(&mut output, &token);
+            }
+        }
+        Ok(output)
+    }
+
+    fn finish_export<T: ExportRow>(exporter: Exporter<T>, label: &str) -> Result<String, String> {
+        let path = exporter.path().display().to_string();
+        let format = exporter.format();
+        let rows = exporter.finish()?;
+        Ok(format!("Exported {rows} {label} to {path} ({}).", format.as_str()))
+    }
+
+    /// `get_all_followers` / `get_all_following`: list every user, or stream
+    /// them page by page into an export file.
+    async fn all_follows(&self, params: GetAllFollowsParams, list: FollowList) -> CallToolResult {
+        let (_account, client, me) = try_ok!(self.require_me_for(params.account.as_deref()).await);
+        let label = list.as_str();
+
+        let Some(path) = params.export_path.as_deref().map(str::trim).filter(|p| !p.is_empty())
+        else {
+            let result = match list {
+                FollowList::Followers => client.get_all_followers(&me.id).await,
+                FollowList::Following => client.get_all_following(&me.id).await,
+            };
+            return Self::ok_or_err(result.map(|users| Self::format_all_follows(&users, label)));
+        };
+
+        let exporter = self.create_exporter::<UserSummary>(path, params.format, params.overwrite);
+        let mut exporter = match exporter {
+            Ok(exporter) => exporter,
+            Err(e) => return CallToolResult::error(vec![Content::text(e)]),
+        };
+        let on_page = |page: Vec<UserSummary>| exporter.write_all(&page);
+        let result = match list {
+            FollowList::Followers => client.stream_followers(&me.id, on_page).await,
+            FollowList::Following => client.stream_following(&me.id, on_page).await,
+        };
+        Self::ok_or_err(result.and_then(|_| Self::finish_export(exporter, label)))
+    }
 }
 
 #[tool_router]
@@ -359,12 +1557,18 @@ impl PostXServer {
                  All tools accept an optional 'account' parameter to select \
                  which X account to use (omit for default). \
                  Available accounts: {}. \
-                 Tools: post_tweet, post_thread, upload_media, \
-                 delete_tweet, search_tweets, get_timeline, get_me, lookup_user, \
-                 get_followers, get_following, get_all_followers, get_all_following, \
-                 follow_user, unfollow_user, like_tweet, unlike_tweet, retweet, \
-                 unretweet, get_dm_events, send_dm, list_accounts.",
-                accounts_str.join(", ")
+                 Tools: post_tweet, post_to_accounts, post_from_template, post_thread, \
+                 post_thread_from_file, resume_thread, schedule_tweet, schedule_thread, \
+                 list_scheduled, cancel_scheduled, list_recurring, save_draft, list_drafts, \
+                 update_draft, delete_draft, publish_draft, get_post_history, \
+                 tweet_performance, edit_tweet, upload_media, delete_tweet, get_tweet, \
+                 get_conversation, unroll_thread, search_tweets, get_timeline, get_me, \
+                 lookup_user, get_followers, get_following, get_all_followers, \
+                 get_all_following, snapshot_followers, follower_changes, follow_user, \
+                 unfollow_user, like_tweet, unlike_tweet, retweet, unretweet, get_dm_events, \
+                 send_dm, list_accounts.{}",
+                accounts_str.join(", "),
+                Self::describe_templates(&config.templates)
             )
         };
 
@@ -372,6 +1576,25 @@ impl PostXServer {
             clients,
             default_account,
             cached_me: Arc::new(Mutex::new(HashMap::new())),
+            thread_jobs: Arc::new(JsonStore::new(config.data_dir.join("threads"))),
+            scheduled: Arc::new(JsonStore::new(config.data_dir.join("scheduled"))),
+            data_dir: config.data_dir.clone(),
+            duplicates: Arc::new(DuplicateGuard::new(config.data_dir.join("posted"))),
+            templates: Arc::new(config.templates),
+            mentions: Arc::new(MentionChecker::new()),
+            recurring: Arc::new(config.recurring),
+            recurring_state: Arc::new(JsonStore::new(config.data_dir.join("recurring"))),
+            evergreen_state: Arc::new(JsonStore::new(config.data_dir.join("evergreen"))),
+            drafts: Arc::new(JsonStore::new(config.data_dir.join("drafts"))),
+            history: Arc::new(PostHistory::new(config.data_dir.join("history.sqlite3"))),
+            audit: config
+                .audit
+                .map(|audit| Arc::new(AuditLog::new(audit, &config.data_dir))),
+            snapshots: Arc::new(SnapshotStore::new(config.data_dir.join("followers"))),
+            snapshot_schedule: config.follower_snapshots.map(Arc::new),
+            snapshot_state: Arc::new(JsonStore::new(config.data_dir.join("followers"))),
+            engagement: config.engagement.map(Arc::new),
+            engagement_state: Arc::new(JsonStore::new(config.data_dir.join("engagement"))),
             instructions,
             tool_router: Self::tool_router(),
         }
@@ -400,7 +1623,7 @@ impl PostXServer {
     }
 
     #[tool(
-        description = "Post a single tweet to X (Twitter). Supports text with optional media: up to 4 images, or 1 video, or 1 GIF. Use 'media' to upload+attach files, or 'media_ids' for pre-uploaded media (not both)."
+        description = "Post a single tweet to X (Twitter). Supports text with optional media: up to 4 images, or 1 video, or 1 GIF. Use 'media' to upload+attach files, or 'media_ids' for pre-uploaded media (not both). Set 'quote_tweet_id' to quote another tweet."
     )]
     async fn post_tweet(
         &self,
@@ -414,6 +1637,16 @@ impl PostXServer {
             )]));
         }
 
+        let quote = match params.quote_tweet_id.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+        // Normalized so a reply by URL and by ID fingerprint the same.
+        let reply_to = match params.reply_to.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+
         let (account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
@@ -424,150 +1657,1294 @@ impl PostXServer {
             .map(Into::into)
             .collect();
 
+        let window_secs = client.config().duplicate_window_hours * 3600;
+        let fingerprint = if window_secs > 0 {
+            match DuplicateGuard::fingerprint(
+                &params.text,
+                &media_attachments,
+                params.media_ids.as_deref(),
+                reply_to.as_deref(),
+                quote.as_deref(),
+            ) {
+                Ok(hash) => Some(hash),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            }
+        } else {
+            None
+        };
+        if let Some(hash) = &fingerprint
+            && params.allow_duplicate != Some(true)
+        {
+            match self.duplicates.find_recent(&account, hash, window_secs) {
+                Ok(Some(previous)) => return Ok(Self::duplicate_refusal(&account, &previous)),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Duplicate check failed for {account}: {e}"),
+            }
+        }
+
+        let strict_mentions = client.config().strict_mentions;
+        let mentions = self.mentions.check(&client, &params.text).await;
+        if strict_mentions && !mentions.problems.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Refused: the tweet mentions handle(s) that can't be reached:\n  - {}\n\
+                 Fix the handles, or set strict_mentions = false for account '{account}' \
+                 to post with a warning instead.",
+                mentions.problems.join("\n  - ")
+            ))]));
+        }
+
+        if params.dry_run == Some(true) {
+            let preview = client.preview_tweet(
+                &params.text,
+                &media_attachments,
+                params.media_ids.as_deref(),
+                reply_to.as_deref(),
+                quote.as_deref(),
+            );
+            return Ok(Self::ok_or_err(preview.map(|p| {
+                let mut output = Self::dry_run_header(&account);
+                Self::format_tweet_preview(&mut output, &p);
+                Self::append_mention_warnings(&mut output, &mentions, strict_mentions);
+                output
+            })));
+        }
+
+        // Checked again and reserved under the guard's lock, so two identical
+        // calls racing past the check above can't both post.
+        let mut reserved = false;
+        if let Some(hash) = &fingerprint
+            && params.allow_duplicate != Some(true)
+        {
+            match self.duplicates.reserve(&account, hash, window_secs) {
+                Ok(Some(previous)) => return Ok(Self::duplicate_refusal(&account, &previous)),
+                Ok(None) => reserved = true,
+                Err(e) => tracing::warn!("Duplicate check failed for {account}: {e}"),
+            }
+        }
+
         let result = client
             .post_tweet(
                 &params.text,
                 &media_attachments,
                 params.media_ids.as_deref(),
-                params.reply_to.as_deref(),
+                reply_to.as_deref(),
+                quote.as_deref(),
                 &me.username,
             )
             .await;
 
-        Ok(Self::ok_or_err(
-            result.map(|r| Self::format_post_result(&r, &account)),
-        ))
+        if let Ok(post) = &result {
+            let mut entry = HistoryEntry::new(HistoryAction::Tweet, &account, &post.tweet_id);
+            entry.url = Some(post.url.clone());
+            entry.text = Some(post.text.clone());
+            entry.media = media_attachments.iter().map(|m| m.path.clone()).collect();
+            entry.reply_to = reply_to.clone();
+            entry.quote_of = quote.clone();
+            self.record_history(entry);
+        }
+        if let Some(hash) = fingerprint {
+            let saved = match &result {
+                Ok(post) => {
+                    self.duplicates
+                        .record(&account, hash, &post.tweet_id, &post.url, window_secs)
+                }
+                Err(_) if reserved => self.duplicates.release(&account, &hash),
+                Err(_) => Ok(()),
+            };
+            if let Err(e) = saved {
+                tracing::warn!("Failed to record post fingerprint for {account}: {e}");
+            }
+        }
+
+        Ok(Self::ok_or_err(result.map(|r| {
+            let mut output = Self::format_post_result(&r, &account);
+            Self::append_mention_warnings(&mut output, &mentions, strict_mentions);
+            output
+        })))
     }
 
     #[tool(
-        description = "Post a thread of tweets to X (Twitter). Each tweet can optionally include media attachments. Max 25 tweets per thread."
+        description = "Cross-post the same tweet (text and optional media) from several configured accounts at once. Media is uploaded once per account and the accounts post concurrently. Reports success or error per account."
     )]
-    async fn post_thread(
+    async fn post_to_accounts(
         &self,
-        Parameters(params): Parameters<PostThreadParams>,
+        Parameters(params): Parameters<PostToAccountsParams>,
     ) -> Result<CallToolResult, McpError> {
-        if params.tweets.is_empty() {
-            return Err(McpError::invalid_params(
-                "Thread must contain at least one tweet",
-                None,
-            ));
+        let mut accounts: Vec<String> = Vec::new();
+        for name in &params.accounts {
+            let (name, _) = try_tool!(self.require_account(Some(name)));
+            if !accounts.iter().any(|a| a == name) {
+                accounts.push(name.to_string());
+            }
         }
-        if params.tweets.len() > 25 {
+        if accounts.is_empty() {
             return Err(McpError::invalid_params(
-                "Thread cannot exceed 25 tweets",
+                "accounts must list at least one account",
                 None,
             ));
         }
 
-        let (account, client, me) =
-            try_tool!(self.require_me_for(params.account.as_deref()).await);
-
-        let tweets: Vec<(String, Vec<MediaAttachment>)> = params
-            .tweets
-            .into_iter()
-            .map(|t| {
-                let media = t
-                    .media
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(Into::into)
-                    .collect();
-                (t.text, media)
+        let handles: Vec<_> = accounts
+            .iter()
+            .map(|account| {
+                let server = self.clone();
+                let params = PostTweetParams {
+                    account: Some(account.clone()),
+                    text: params.text.clone(),
+                    media: params.media.clone(),
+                    media_ids: None,
+                    reply_to: None,
+                    quote_tweet_id: None,
+                    allow_duplicate: params.allow_duplicate,
+                    dry_run: params.dry_run,
+                };
+                tokio::spawn(async move { server.post_tweet(Parameters(params)).await })
             })
             .collect();
 
-        let tweet_count = tweets.len();
-        let result = client.post_thread(&tweets, &me.username).await;
-
-        let mut output = String::new();
-        if !result.posted.is_empty() {
-            output.push_str(&format!(
-                "Posted {}/{} tweets as @{account}:\n",
-                result.posted.len(),
-                tweet_count
-            ));
-            for (i, post) in result.posted.iter().enumerate() {
-                output.push_str(&format!(
-                    "  {}. ID: {} — {}\n",
-                    i + 1,
-                    post.tweet_id,
-                    post.url
-                ));
+        let mut succeeded = 0;
+        let mut report = String::new();
+        for (account, handle) in accounts.iter().zip(handles) {
+            let (ok, text) = match handle.await {
+                Ok(Ok(result)) => (result.is_error != Some(true), Self::result_text(&result)),
+                Ok(Err(e)) => (false, e.message.to_string()),
+                Err(e) => (false, format!("Task failed: {e}")),
+            };
+            if ok {
+                succeeded += 1;
             }
+            let status = if ok { "OK" } else { "ERROR" };
+            report.push_str(&format!("=== {account}: {status} ===\n{}\n\n", text.trim_end()));
         }
 
-        if let Some(err) = &result.error {
-            output.push_str(&format!("\nError: {err}"));
+        let verb = if params.dry_run == Some(true) {
+            "Validated"
+        } else {
+            "Posted"
+        };
+        let output = format!(
+            "{verb} for {succeeded}/{} account(s).\n\n{}",
+            accounts.len(),
+            report.trim_end()
+        );
+        if succeeded < accounts.len() {
             return Ok(CallToolResult::error(vec![Content::text(output)]));
         }
-
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     #[tool(
-        description = "Upload media to X (Twitter) for later attachment via media_ids. Returns a media_id. Supports: jpeg/png/webp (max 5MB), gif (max 15MB), mp4 video (max 512MB). Alt text supported for images and GIFs only."
+        description = "Post a tweet rendered from a named template in the [templates] config section. Fills {placeholders} from 'variables', uses the template's default media unless 'media' is given, appends the account's signature and checks the length, then posts exactly like post_tweet."
     )]
-    async fn upload_media(
+    async fn post_from_template(
         &self,
-        Parameters(params): Parameters<UploadMediaParams>,
+        Parameters(params): Parameters<PostFromTemplateParams>,
     ) -> Result<CallToolResult, McpError> {
+        let Some(template) = self.templates.get(&params.template) else {
+            let mut names: Vec<&str> = self.templates.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            let available = if names.is_empty() {
+                "none configured".to_string()
+            } else {
+                names.join(", ")
+            };
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown template '{}'. Available: {available}",
+                params.template
+            ))]));
+        };
+
         let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let config = client.config();
+        let variables = params.variables.unwrap_or_default();
+        let text = match template.render(&variables, config.signature.as_deref()) {
+            Ok(text) => text,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Template '{}': {e}",
+                    params.template
+                ))]));
+            }
+        };
 
-        let result = client
-            .upload_media(&params.path, params.alt_text.as_deref())
-            .await;
+        let length = text::weighted_length(&client.prepare_text(&text).text);
+        if length > config.max_tweet_length {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Rendered tweet is too long: {length}/{} weighted characters\n\n{text}",
+                config.max_tweet_length
+            ))]));
+        }
 
-        Ok(Self::ok_or_err(result.map(|r| {
-            format!(
-                "Media uploaded (account: {account})!\nMedia ID: {}\nType: {}\nState: {}",
-                r.media_id, r.media_type, r.state
-            )
-        })))
+        let media = params.media.unwrap_or_else(|| {
+            template
+                .media
+                .iter()
+                .map(|m| MediaAttachmentParam {
+                    path: m.path.clone(),
+                    alt_text: m.alt_text.clone(),
+                })
+                .collect()
+        });
+
+        self.post_tweet(Parameters(PostTweetParams {
+            account: Some(account.to_string()),
+            text,
+            media: Some(media),
+            media_ids: None,
+            reply_to: params.reply_to,
+            quote_tweet_id: None,
+            allow_duplicate: params.allow_duplicate,
+            dry_run: params.dry_run,
+        }))
+        .await
     }
 
     #[tool(
-        description = "Get the authenticated X (Twitter) user's profile (id, name, username). Useful for verifying credentials."
+        description = "Edit a tweet on X (Twitter) by publishing a new version of it. Accepts the original tweet ID or URL plus the new text and optional media. Only works within X's edit window and edit limit."
     )]
-    async fn get_me(
+    async fn edit_tweet(
         &self,
-        Parameters(params): Parameters<AccountOnlyParams>,
+        Parameters(params): Parameters<EditTweetParams>,
     ) -> Result<CallToolResult, McpError> {
-        let (name, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
 
-        match client.get_me().await {
-            Ok(me) => {
-                {
-                    let mut cached = self.cached_me.lock().await;
-                    cached.insert(name.to_string(), me.clone());
-                }
-                let text = format!(
-                    "Authenticated as (account: {name}):\n  Name: {}\n  Username: @{}\n  ID: {}",
-                    me.name, me.username, me.id
-                );
-                Ok(CallToolResult::success(vec![Content::text(text)]))
-            }
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        let has_media = params.media.as_ref().is_some_and(|m| !m.is_empty());
+        let has_media_ids = params.media_ids.as_ref().is_some_and(|ids| !ids.is_empty());
+        if has_media && has_media_ids {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "'media' and 'media_ids' are mutually exclusive. Use one or the other.",
+            )]));
         }
-    }
 
-    #[tool(
-        description = "Get the authenticated user's followers on X (Twitter). Returns usernames, display names, follower counts, and bios."
-    )]
-    async fn get_followers(
-        &self,
-        Parameters(params): Parameters<FollowsLookupParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let (_account, client, me) =
+        let (account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
-        let max_results = params.max_results.unwrap_or(20).clamp(1, 100);
+        let media_attachments: Vec<MediaAttachment> = params
+            .media
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect();
 
         let result = client
-            .get_followers(&me.id, max_results, params.pagination_token.as_deref())
-            .await;
-
-        Ok(Self::ok_or_err(
-            result.map(|r| Self::format_follows(&r.users, &r.next_token, "followers")),
+            .edit_tweet(
+                tweet_id,
+                &params.text,
+                &media_attachments,
+                params.media_ids.as_deref(),
+                &me.username,
+            )
+            .await;
+
+        Ok(Self::ok_or_err(result.map(|r| {
+            let mut output = format!(
+                "Tweet {tweet_id} edited as @{account}!\nNew version ID: {}\nURL: {}",
+                r.tweet_id, r.url
+            );
+            Self::append_transformations(&mut output, &r.transformations, "");
+            output
+        })))
+    }
+
+    #[tool(
+        description = "Post a thread of tweets to X (Twitter). Each tweet can optionally include media attachments. Max 25 tweets per thread. Set reply_to to post the thread under an existing tweet. Set on_failure to 'rollback' to delete already-posted tweets if any tweet fails."
+    )]
+    async fn post_thread(
+        &self,
+        Parameters(params): Parameters<PostThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.tweets.is_empty() {
+            return Err(McpError::invalid_params(
+                "Thread must contain at least one tweet",
+                None,
+            ));
+        }
+        if params.tweets.len() > 25 {
+            return Err(McpError::invalid_params(
+                "Thread cannot exceed 25 tweets",
+                None,
+            ));
+        }
+
+        let tweets: Vec<(String, Vec<MediaAttachment>)> = params
+            .tweets
+            .into_iter()
+            .map(|t| {
+                let media = t
+                    .media
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+                (t.text, media)
+            })
+            .collect();
+
+        Ok(self
+            .run_thread(
+                params.account.as_deref(),
+                tweets,
+                params.reply_to.as_deref(),
+                params.on_failure.unwrap_or_default(),
+                params.dry_run == Some(true),
+            )
+            .await)
+    }
+
+    #[tool(
+        description = "Post a thread drafted as a Markdown file. Tweets are separated by lines containing only '---'; images written as ![alt text](relative/path.png) are attached with that alt text. Optional front-matter at the top sets 'account' and 'reply_to'; explicit parameters override it."
+    )]
+    async fn post_thread_from_file(
+        &self,
+        Parameters(params): Parameters<PostThreadFromFileParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Ok(self.post_thread_file(params).await)
+    }
+
+    #[tool(
+        description = "Schedule a tweet to be posted at a future time (RFC 3339, e.g. 2025-06-01T09:00:00+02:00). The tweet is validated now and stored in a local queue that survives restarts. Set if_missed to 'skip' to drop it if no server is running at that time."
+    )]
+    async fn schedule_tweet(
+        &self,
+        Parameters(params): Parameters<ScheduleTweetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let post_at = try_tool!(Self::parse_post_at(&params.post_at));
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let reply_to = match params.reply_to.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+        let media: Vec<MediaAttachment> = params
+            .media
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let preview = client.preview_tweet(&params.text, &media, None, reply_to.as_deref(), None);
+        if let Err(e) = preview {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Not scheduled: {e}"
+            ))]));
+        }
+
+        let now = now_unix();
+        let job = ScheduledPost {
+            id: new_id("sched"),
+            account: account.to_string(),
+            content: ScheduledContent::Tweet {
+                text: params.text,
+                media,
+                reply_to,
+            },
+            post_at,
+            if_missed: params.if_missed.unwrap_or_default(),
+            status: ScheduledStatus::Pending,
+            posted: Vec::new(),
+            error: None,
+            thread_job_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(self.enqueue_scheduled(job, "tweet"))
+    }
+
+    #[tool(
+        description = "Schedule a thread (max 25 tweets) to be posted at a future time (RFC 3339). The thread is validated now and stored in a local queue that survives restarts. Set if_missed to 'skip' to drop it if no server is running at that time."
+    )]
+    async fn schedule_thread(
+        &self,
+        Parameters(params): Parameters<ScheduleThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.tweets.is_empty() {
+            return Err(McpError::invalid_params(
+                "Thread must contain at least one tweet",
+                None,
+            ));
+        }
+        if params.tweets.len() > 25 {
+            return Err(McpError::invalid_params(
+                "Thread cannot exceed 25 tweets",
+                None,
+            ));
+        }
+
+        let post_at = try_tool!(Self::parse_post_at(&params.post_at));
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let reply_to = match params.reply_to.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+        let tweets: Vec<(String, Vec<MediaAttachment>)> = params
+            .tweets
+            .into_iter()
+            .map(|t| {
+                let media = t
+                    .media
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+                (t.text, media)
+            })
+            .collect();
+
+        if let Err(e) = client.preview_thread(&tweets, reply_to.as_deref()) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Not scheduled: {e}"
+            ))]));
+        }
+
+        let now = now_unix();
+        let count = tweets.len();
+        let job = ScheduledPost {
+            id: new_id("sched"),
+            account: account.to_string(),
+            content: ScheduledContent::Thread {
+                tweets: tweets
+                    .into_iter()
+                    .map(|(text, media)| ThreadJobTweet { text, media })
+                    .collect(),
+                reply_to,
+            },
+            post_at,
+            if_missed: params.if_missed.unwrap_or_default(),
+            status: ScheduledStatus::Pending,
+            posted: Vec::new(),
+            error: None,
+            thread_job_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(self.enqueue_scheduled(job, &format!("thread of {count} tweets")))
+    }
+
+    #[tool(
+        description = "List scheduled posts, soonest first. Pending only by default; set include_finished to also see posted, failed, skipped and cancelled jobs with their outcomes."
+    )]
+    async fn list_scheduled(
+        &self,
+        Parameters(params): Parameters<ListScheduledParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let account = match params.account.as_deref() {
+            Some(name) => Some(try_tool!(self.require_account(Some(name))).0),
+            None => None,
+        };
+        let mut jobs = match self.scheduled.list() {
+            Ok(jobs) => jobs,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let include_finished = params.include_finished == Some(true);
+        jobs.retain(|j| {
+            account.is_none_or(|a| j.account == a)
+                && (include_finished || j.status == ScheduledStatus::Pending)
+        });
+        jobs.sort_by_key(|j| j.post_at);
+
+        if jobs.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No scheduled posts.",
+            )]));
+        }
+        let now = now_unix();
+        let mut output = format!("Scheduled posts ({}):\n", jobs.len());
+        for job in &jobs {
+            output.push_str(&Self::format_scheduled(job, now));
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Cancel a pending scheduled post by its job ID.")]
+    async fn cancel_scheduled(
+        &self,
+        Parameters(params): Parameters<CancelScheduledParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut job = match self.scheduled.load(params.job_id.trim()) {
+            Ok(job) => job,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Scheduled post {e}"
+                ))]));
+            }
+        };
+        if job.status != ScheduledStatus::Pending {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Scheduled post {} can't be cancelled: it is {}.",
+                job.id,
+                Self::status_name(job.status)
+            ))]));
+        }
+        job.status = ScheduledStatus::Cancelled;
+        self.save_scheduled(&mut job);
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Cancelled scheduled post {} (was due {}).",
+            job.id,
+            Self::format_unix(job.post_at)
+        ))]))
+    }
+
+    #[tool(
+        description = "List recurring jobs from the config with their cron schedule, next and last run, and the outcome of the last run. Evergreen jobs also show how many posts in their queue are eligible (not used within the repeat window)."
+    )]
+    async fn list_recurring(
+        &self,
+        Parameters(params): Parameters<ListRecurringParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let account = match params.account.as_deref() {
+            Some(name) => Some(try_tool!(self.require_account(Some(name))).0),
+            None => None,
+        };
+        let jobs: Vec<&RecurringJob> = self
+            .recurring
+            .jobs
+            .iter()
+            .filter(|j| {
+                account.is_none_or(|a| j.account.as_deref().unwrap_or(&self.default_account) == a)
+            })
+            .collect();
+        if jobs.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No recurring jobs. Add [[recurring]] entries to the config file.",
+            )]));
+        }
+
+        let now = now_unix();
+        let mut output = format!("Recurring jobs ({}):\n", jobs.len());
+        for job in jobs {
+            let state = self
+                .recurring_state
+                .get(&job.name)
+                .ok()
+                .flatten()
+                .filter(|s| s.schedule == job.schedule_key())
+                .unwrap_or_default();
+            let next = match state.next_run.or_else(|| {
+                job.schedule().ok().and_then(|s| s.next_after(now))
+            }) {
+                Some(ts) => format!(
+                    "{} ({})",
+                    Self::format_unix(ts),
+                    Self::format_relative(ts, now)
+                ),
+                None => "never".to_string(),
+            };
+            output.push_str(&format!(
+                "- {} | {} | {} ({}) | next: {next}\n",
+                job.name,
+                job.account.as_deref().unwrap_or(&self.default_account),
+                job.cron,
+                job.timezone
+            ));
+            match (&job.text, &job.evergreen) {
+                (Some(text), _) => output.push_str(&format!(
+                    "    Text: \"{}\"\n",
+                    Self::truncate_str(text, 60)
+                )),
+                (None, Some(name)) => {
+                    let queue = &self.recurring.evergreen[name];
+                    let queue_state =
+                        self.evergreen_state.get(name).ok().flatten().unwrap_or_default();
+                    output.push_str(&format!(
+                        "    Evergreen: {name} ({} of {} posts eligible, {}-day repeat window)\n",
+                        queue.eligible_count(&queue_state, now),
+                        queue.posts.len(),
+                        queue.min_days_between_repeats
+                    ));
+                }
+                (None, None) => {}
+            }
+            if let (Some(ts), Some(outcome)) = (state.last_run, &state.last_outcome) {
+                output.push_str(&format!(
+                    "    Last run: {} — {outcome}\n",
+                    Self::format_unix(ts)
+                ));
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Save a tweet or thread as a local draft for a human to review before it is posted. Drafts persist across sessions. Set 'text' (with optional media, reply_to and quote_tweet_id) for a single tweet, or 'tweets' for a thread. The draft is checked and any problem reported, but it is saved either way."
+    )]
+    async fn save_draft(
+        &self,
+        Parameters(params): Parameters<SaveDraftParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let reply_to = match params.reply_to.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+        let quote_tweet_id = match params.quote_tweet_id.as_deref() {
+            Some(raw) => Some(try_tool!(Self::require_tweet_id(raw)).to_string()),
+            None => None,
+        };
+        let content = match (params.text, params.tweets) {
+            (Some(text), None) => DraftContent::Tweet {
+                text,
+                media: params
+                    .media
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                quote_tweet_id,
+            },
+            (None, Some(tweets)) => {
+                if params.media.is_some() || quote_tweet_id.is_some() {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "'media' and 'quote_tweet_id' apply to single-tweet drafts. \
+                         Put media on each tweet of a thread instead.",
+                    )]));
+                }
+                DraftContent::Thread {
+                    tweets: try_tool!(Self::draft_tweets(tweets)),
+                }
+            }
+            _ => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Set exactly one of 'text' (single tweet) or 'tweets' (thread).",
+                )]));
+            }
+        };
+
+        let now = now_unix();
+        let draft = Draft {
+            id: new_id("draft"),
+            account: account.to_string(),
+            content,
+            reply_to,
+            note: params.note.filter(|n| !n.trim().is_empty()),
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(self.store_draft(&draft, client, "Saved"))
+    }
+
+    #[tool(
+        description = "List saved drafts with their full text, media, reply/quote targets and reviewer notes, oldest first."
+    )]
+    async fn list_drafts(
+        &self,
+        Parameters(params): Parameters<ListDraftsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let account = match params.account.as_deref() {
+            Some(name) => Some(try_tool!(self.require_account(Some(name))).0),
+            None => None,
+        };
+        let mut drafts = match self.drafts.list() {
+            Ok(drafts) => drafts,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        drafts.retain(|d| account.is_none_or(|a| d.account == a));
+        drafts.sort_by_key(|d| d.created_at);
+
+        if drafts.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text("No drafts.")]));
+        }
+        let now = now_unix();
+        let mut output = format!("Drafts ({}):\n", drafts.len());
+        for draft in &drafts {
+            output.push_str(&Self::format_draft(draft, now));
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Change a saved draft. Only the fields you pass are changed; pass an empty string to clear reply_to, quote_tweet_id or note. Passing 'tweets' turns a single-tweet draft into a thread, and 'text' turns a thread into a single tweet."
+    )]
+    async fn update_draft(
+        &self,
+        Parameters(params): Parameters<UpdateDraftParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut draft = match self.drafts.load(params.draft_id.trim()) {
+            Ok(draft) => draft,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Draft {e}"
+                ))]));
+            }
+        };
+        if params.text.is_some() && params.tweets.is_some() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Set at most one of 'text' (single tweet) or 'tweets' (thread).",
+            )]));
+        }
+
+        let current = draft.account.clone();
+        let account = params.account.as_deref().unwrap_or(&current);
+        let (account, client) = try_tool!(self.require_account(Some(account)));
+        let account = account.to_string();
+        let reply_to = try_tool!(Self::clearable_tweet_id(params.reply_to));
+        let quote = try_tool!(Self::clearable_tweet_id(params.quote_tweet_id));
+
+        if let Some(tweets) = params.tweets {
+            draft.content = DraftContent::Thread {
+                tweets: try_tool!(Self::draft_tweets(tweets)),
+            };
+        } else if let Some(text) = params.text {
+            draft.content = match draft.content {
+                DraftContent::Tweet {
+                    media,
+                    quote_tweet_id,
+                    ..
+                } => DraftContent::Tweet {
+                    text,
+                    media,
+                    quote_tweet_id,
+                },
+                DraftContent::Thread { .. } => DraftContent::Tweet {
+                    text,
+                    media: Vec::new(),
+                    quote_tweet_id: None,
+                },
+            };
+        }
+        match &mut draft.content {
+            DraftContent::Tweet {
+                media,
+                quote_tweet_id,
+                ..
+            } => {
+                if let Some(new_media) = params.media {
+                    *media = new_media.into_iter().map(Into::into).collect();
+                }
+                if let Some(new_quote) = quote {
+                    *quote_tweet_id = new_quote;
+                }
+            }
+            DraftContent::Thread { .. } => {
+                if params.media.is_some() || quote.is_some_and(|q| q.is_some()) {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "'media' and 'quote_tweet_id' apply to single-tweet drafts. \
+                         Put media on each tweet of a thread instead.",
+                    )]));
+                }
+            }
+        }
+
+        draft.account = account;
+        if let Some(reply_to) = reply_to {
+            draft.reply_to = reply_to;
+        }
+        if let Some(note) = Self::clearable(params.note) {
+            draft.note = note;
+        }
+        draft.updated_at = now_unix();
+        Ok(self.store_draft(&draft, client, "Updated"))
+    }
+
+    #[tool(description = "Delete a saved draft without posting it.")]
+    async fn delete_draft(
+        &self,
+        Parameters(params): Parameters<DraftIdParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let id = params.draft_id.trim();
+        Ok(match self.drafts.delete(id) {
+            Ok(true) => {
+                CallToolResult::success(vec![Content::text(format!("Deleted draft {id}."))])
+            }
+            Ok(false) => {
+                CallToolResult::error(vec![Content::text(format!("Draft '{id}' not found"))])
+            }
+            Err(e) => CallToolResult::error(vec![Content::text(e)]),
+        })
+    }
+
+    #[tool(
+        description = "Publish a saved draft through the normal post_tweet or post_thread path (same validation, duplicate and mention checks). The draft is removed once it is posted, and kept if posting fails or dry_run is set."
+    )]
+    async fn publish_draft(
+        &self,
+        Parameters(params): Parameters<PublishDraftParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let id = params.draft_id.trim();
+        let dry_run = params.dry_run == Some(true);
+        if let Err(e) = self.drafts.load(id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Draft {e}"
+            ))]));
+        }
+        // Claimed before re-reading, so a concurrent publish is refused and a
+        // later one finds the draft already removed.
+        let _claim = if dry_run {
+            None
+        } else {
+            match self.claim(&format!("draft-{id}")) {
+                Ok(Some(claim)) => Some(claim),
+                Ok(None) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Draft {id} is already being published by another call."
+                    ))]));
+                }
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            }
+        };
+        let draft = match self.drafts.load(id) {
+            Ok(draft) => draft,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Draft {e}"
+                ))]));
+            }
+        };
+
+        let (result, is_thread) = match &draft.content {
+            DraftContent::Tweet {
+                text,
+                media,
+                quote_tweet_id,
+            } => {
+                let media = media
+                    .iter()
+                    .map(|m| MediaAttachmentParam {
+                        path: m.path.clone(),
+                        alt_text: m.alt_text.clone(),
+                    })
+                    .collect();
+                let result = self
+                    .post_tweet(Parameters(PostTweetParams {
+                        account: Some(draft.account.clone()),
+                        text: text.clone(),
+                        media: Some(media),
+                        media_ids: None,
+                        reply_to: draft.reply_to.clone(),
+                        quote_tweet_id: quote_tweet_id.clone(),
+                        allow_duplicate: params.allow_duplicate,
+                        dry_run: params.dry_run,
+                    }))
+                    .await?;
+                (result, false)
+            }
+            DraftContent::Thread { tweets } => {
+                let tweets = tweets
+                    .iter()
+                    .map(|t| (t.text.clone(), t.media.clone()))
+                    .collect();
+                let result = self
+                    .run_thread(
+                        Some(&draft.account),
+                        tweets,
+                        draft.reply_to.as_deref(),
+                        params.on_failure.unwrap_or_default(),
+                        dry_run,
+                    )
+                    .await;
+                (result, true)
+            }
+        };
+
+        let mut output = Self::result_text(&result);
+        if result.is_error == Some(true) {
+            output.push_str(&format!("\n\nDraft {} was kept.", draft.id));
+            if is_thread && !dry_run {
+                output.push_str(
+                    " If part of the thread was posted, finish it with resume_thread \
+                     instead of publishing the draft again.",
+                );
+            }
+            return Ok(CallToolResult::error(vec![Content::text(output)]));
+        }
+        if !dry_run {
+            match self.drafts.delete(&draft.id) {
+                Ok(_) => output.push_str(&format!(
+                    "\n\nDraft {} was published and removed.",
+                    draft.id
+                )),
+                Err(e) => output.push_str(&format!(
+                    "\n\nWarning: draft {} was published but could not be removed: {e}",
+                    draft.id
+                )),
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Report how tweets posted by this server performed, from metrics stored over time: per-account averages, top posts by engagement rate with their growth curves, or every sample of one tweet. Metrics are collected in the background when [engagement] is configured; set refresh to fetch current numbers from X first."
+    )]
+    async fn tweet_performance(
+        &self,
+        Parameters(params): Parameters<TweetPerformanceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let account = match params.account.as_deref() {
+            Some(name) => Some(try_tool!(self.require_account(Some(name))).0),
+            None => None,
+        };
+        let days = params.days.unwrap_or(7).clamp(1, 365);
+        let now = now_unix();
+        let since = now.saturating_sub(days * 86_400);
+
+        let mut output = String::new();
+        if params.refresh == Some(true) {
+            let private = self.engagement.as_ref().is_none_or(|c| c.private_metrics);
+            match self.refresh_metrics(account, since, private).await {
+                Ok(summary) => output.push_str(&format!("Refreshed: {summary}\n\n")),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            }
+        }
+
+        if let Some(raw) = params.tweet_id.as_deref() {
+            let id = try_tool!(Self::require_tweet_id(raw));
+            return Ok(Self::ok_or_err(
+                self.tweet_performance_detail(id, now).map(|detail| output + &detail),
+            ));
+        }
+
+        let (posts, samples) = match self
+            .history
+            .live_posts(account, since)
+            .and_then(|posts| Ok((posts, self.history.metrics_since(account, since)?)))
+        {
+            Ok(found) => found,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let mut series: HashMap<String, Vec<MetricsSample>> = HashMap::new();
+        for sample in samples {
+            series.entry(sample.tweet_id.clone()).or_default().push(sample);
+        }
+        if series.is_empty() {
+            output.push_str(&format!(
+                "No metrics stored for the {} tweet(s) posted in the last {days} day(s). Add an \
+                 [engagement] section to the config to track them, or pass refresh=true.",
+                posts.len()
+            ));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        output.push_str(&format!(
+            "Tweet performance, last {days} day(s): {} tweet(s), {} with metrics\n",
+            posts.len(),
+            series.len()
+        ));
+
+        // Per-account averages over each tweet's latest sample.
+        let mut per_account: BTreeMap<&str, Vec<&MetricsSample>> = BTreeMap::new();
+        for samples in series.values() {
+            if let Some(latest) = samples.last() {
+                per_account.entry(&latest.account).or_default().push(latest);
+            }
+        }
+        output.push_str("\nPer-account averages:\n");
+        for (account, latest) in &per_account {
+            let count = latest.len() as u64;
+            let engagements: u64 = latest.iter().map(|s| s.engagements()).sum();
+            let measured: Vec<&&MetricsSample> =
+                latest.iter().filter(|s| s.impressions.is_some()).collect();
+            let impressions: u64 = measured.iter().filter_map(|s| s.impressions).sum();
+            let measured_engagements: u64 = measured.iter().map(|s| s.engagements()).sum();
+            let avg_impressions = if measured.is_empty() {
+                "n/a".to_string()
+            } else {
+                (impressions / measured.len() as u64).to_string()
+            };
+            output.push_str(&format!(
+                "- {account}: {count} tweet(s) | {avg_impressions} impressions | {:.1} engagements \
+                 | {} engagement rate\n",
+                engagements as f64 / count as f64,
+                Self::format_rate(
+                    (impressions > 0).then(|| measured_engagements as f64 / impressions as f64)
+                )
+            ));
+        }
+
+        let top = params.top.unwrap_or(5).clamp(1, 50);
+        let min_impressions = params.min_impressions.unwrap_or(100);
+        let mut ranked: Vec<(&str, f64, &Vec<MetricsSample>)> = series
+            .iter()
+            .filter_map(|(id, samples)| {
+                let latest = samples.last()?;
+                if latest.impressions.unwrap_or(0) < min_impressions {
+                    return None;
+                }
+                Some((id.as_str(), latest.engagement_rate()?, samples))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        if ranked.is_empty() {
+            output.push_str(&format!(
+                "\nNo tweets with at least {min_impressions} impressions to rank yet.\n"
+            ));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        output.push_str(&format!(
+            "\nTop {} by engagement rate (min {min_impressions} impressions):\n",
+            ranked.len().min(top)
+        ));
+        for (i, (id, rate, samples)) in ranked.iter().take(top).enumerate() {
+            let Some(post) = posts.iter().find(|p| p.tweet_id == *id) else {
+                continue;
+            };
+            let latest = samples.last().expect("ranked tweets have samples");
+            output.push_str(&format!(
+                "{}. {} | {} | {} | {} impressions, {} engagements | posted {}\n",
+                i + 1,
+                Self::format_rate(Some(*rate)),
+                post.account,
+                id,
+                latest.impressions.unwrap_or(0),
+                latest.engagements(),
+                Self::format_unix(post.created_at)
+            ));
+            if let Some(text) = &post.text {
+                output.push_str(&format!("   \"{}\"\n", Self::truncate_str(text, 80)));
+            }
+            let curve: Vec<String> = growth_curve(samples, post.created_at)
+                .iter()
+                .map(|s| {
+                    format!(
+                        "{} {}",
+                        Self::format_age(s.fetched_at.saturating_sub(post.created_at)),
+                        s.impressions.unwrap_or(0)
+                    )
+                })
+                .collect();
+            output.push_str(&format!("   Impressions: {}\n", curve.join(" -> ")));
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Search the local history of everything this server posted, deleted, retweeted or liked, newest first. Filter by account, action, date range and text. Works offline; nothing is fetched from X."
+    )]
+    async fn get_post_history(
+        &self,
+        Parameters(params): Parameters<GetPostHistoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let account = match params.account.as_deref() {
+            Some(name) => Some(try_tool!(self.require_account(Some(name))).0),
+            None => None,
+        };
+        let since = match params.since.as_deref() {
+            Some(raw) => Some(try_tool!(
+                parse_bound(raw, false).map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            )),
+            None => None,
+        };
+        let until = match params.until.as_deref() {
+            Some(raw) => Some(try_tool!(
+                parse_bound(raw, true).map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            )),
+            None => None,
+        };
+        let filter = HistoryFilter {
+            account,
+            action: params.action,
+            since,
+            until,
+            contains: params.contains.as_deref().filter(|c| !c.is_empty()),
+            limit: params.limit.unwrap_or(50).clamp(1, 1000),
+        };
+        let entries = match self.history.query(&filter) {
+            Ok(entries) => entries,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if entries.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No matching post history.",
+            )]));
+        }
+
+        let mut output = format!("Post history ({}, newest first):\n", entries.len());
+        for entry in &entries {
+            output.push_str(&format!(
+                "- {} | {} | {} | {}",
+                Self::format_unix(entry.created_at),
+                entry.account,
+                entry.action.as_str(),
+                entry.tweet_id
+            ));
+            if let Some(url) = &entry.url {
+                output.push_str(&format!(" | {url}"));
+            }
+            output.push('\n');
+            if let Some(reply_to) = &entry.reply_to {
+                output.push_str(&format!("    Reply to: {reply_to}\n"));
+            }
+            if let Some(quote) = &entry.quote_of {
+                output.push_str(&format!("    Quote of: {quote}\n"));
+            }
+            if let Some(thread) = &entry.thread_id
+                && thread != &entry.tweet_id
+            {
+                output.push_str(&format!("    Thread: {thread}\n"));
+            }
+            if let Some(text) = &entry.text {
+                output.push_str(&format!("    Text: \"{}\"\n", Self::truncate_str(text, 200)));
+            }
+            if !entry.media.is_empty() {
+                output.push_str(&format!("    Media: {}\n", entry.media.join(", ")));
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Resume a thread that failed partway through. Continues from the failed tweet as a reply to the last posted tweet, without re-posting anything. Takes the thread job ID reported by post_thread."
+    )]
+    async fn resume_thread(
+        &self,
+        Parameters(params): Parameters<ResumeThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let id = params.job_id.trim();
+        if let Err(e) = self.thread_jobs.load(id) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Thread job {e}"
+            ))]));
+        }
+        // Claim before re-reading the job, so two resumes (or a resume racing the
+        // original post_thread) can't both post from the same tweet.
+        let _claim = match self.claim(&format!("thread-{id}")) {
+            Ok(Some(claim)) => claim,
+            Ok(None) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Thread job {id} is being posted by another call. Wait for it to \
+                     finish, then resume again if the thread is still incomplete."
+                ))]));
+            }
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let mut job = match self.thread_jobs.load(id) {
+            Ok(job) => job,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Thread job {e}"
+                ))]));
+            }
+        };
+        if job.status == ThreadJobStatus::RolledBack || !job.rolled_back.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Thread job {} was rolled back and cannot be resumed. \
+                 Post it again with post_thread.",
+                job.id
+            ))]));
+        }
+        if job.status == ThreadJobStatus::Completed {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Thread job {} is already complete ({} tweets posted).",
+                job.id,
+                job.posted.len()
+            ))]));
+        }
+
+        let (account, client, me) = try_tool!(self.require_me_for(Some(&job.account)).await);
+
+        let tweets: Vec<(String, Vec<MediaAttachment>)> = job
+            .tweets
+            .iter()
+            .map(|t| (t.text.clone(), t.media.clone()))
+            .collect();
+        let start = job.posted.len();
+        let reply_to = job.next_reply_to().map(str::to_string);
+
+        job.status = ThreadJobStatus::InProgress;
+        job.failed_index = None;
+        job.error = None;
+        self.save_thread_job(&mut job);
+
+        let result = client
+            .post_thread_from(&tweets, start, reply_to.as_deref(), &me.username, |i, post| {
+                job.posted.push(PostedTweet {
+                    tweet_id: post.tweet_id.clone(),
+                    url: post.url.clone(),
+                });
+                self.save_thread_job(&mut job);
+                self.record_thread_post(&job, i, post);
+            })
+            .await;
+        Self::finish_thread_job(&mut job, &result);
+        self.save_thread_job(&mut job);
+
+        let mut output = format!(
+            "Resumed thread job {} at tweet {} of {}.\n",
+            job.id,
+            start + 1,
+            tweets.len()
+        );
+        if !result.posted.is_empty() {
+            output.push_str(&format!(
+                "Posted {} more tweet(s) as @{account} ({}/{} total):\n",
+                result.posted.len(),
+                job.posted.len(),
+                tweets.len()
+            ));
+            Self::append_thread_posts(&mut output, &result.posted, start);
+        }
+
+        if let Some(err) = &result.error {
+            output.push_str(&format!("\nError: {err}"));
+            Self::append_resume_hint(&mut output, &job);
+            return Ok(CallToolResult::error(vec![Content::text(output)]));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Upload media to X (Twitter) for later attachment via media_ids. Returns a media_id. Supports: jpeg/png/webp (max 5MB), gif (max 15MB), mp4 video (max 512MB). Alt text supported for images and GIFs only."
+    )]
+    async fn upload_media(
+        &self,
+        Parameters(params): Parameters<UploadMediaParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        let result = client
+            .upload_media(&params.path, params.alt_text.as_deref())
+            .await;
+
+        Ok(Self::ok_or_err(result.map(|r| {
+            format!(
+                "Media uploaded (account: {account})!\nMedia ID: {}\nType: {}\nState: {}",
+                r.media_id, r.media_type, r.state
+            )
+        })))
+    }
+
+    #[tool(
+        description = "Get the authenticated X (Twitter) user's profile (id, name, username). Useful for verifying credentials."
+    )]
+    async fn get_me(
+        &self,
+        Parameters(params): Parameters<AccountOnlyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (name, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        match client.get_me().await {
+            Ok(me) => {
+                {
+                    let mut cached = self.cached_me.lock().await;
+                    cached.insert(name.to_string(), me.clone());
+                }
+                let text = format!(
+                    "Authenticated as (account: {name}):\n  Name: {}\n  Username: @{}\n  ID: {}",
+                    me.name, me.username, me.id
+                );
+                Ok(CallToolResult::success(vec![Content::text(text)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+
+    #[tool(
+        description = "Get the authenticated user's followers on X (Twitter). Returns usernames, display names, follower counts, and bios."
+    )]
+    async fn get_followers(
+        &self,
+        Parameters(params): Parameters<FollowsLookupParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (_account, client, me) =
+            try_tool!(self.require_me_for(params.account.as_deref()).await);
+
+        let max_results = params.max_results.unwrap_or(20).clamp(1, 100);
+
+        let result = client
+            .get_followers(&me.id, max_results, params.pagination_token.as_deref())
+            .await;
+
+        Ok(Self::ok_or_err(
+            result.map(|r| Self::format_follows(&r.users, &r.next_token, "followers")),
         ))
     }
 
@@ -593,37 +2970,156 @@ impl PostXServer {
     }
 
     #[tool(
-        description = "Get ALL accounts the authenticated user follows on X (Twitter). Auto-paginates to fetch every account. Returns usernames, display names, follower counts, and bios."
+        description = "Get ALL accounts the authenticated user follows on X (Twitter). Auto-paginates to fetch every account. Returns usernames, display names, follower counts, and bios. Set export_path to stream them to a CSV or JSONL file instead and get back only the path and row count."
     )]
     async fn get_all_following(
         &self,
-        Parameters(params): Parameters<AccountOnlyParams>,
+        Parameters(params): Parameters<GetAllFollowsParams>,
     ) -> Result<CallToolResult, McpError> {
-        let (_account, client, me) =
-            try_tool!(self.require_me_for(params.account.as_deref()).await);
+        Ok(self.all_follows(params, FollowList::Following).await)
+    }
 
-        let result = client.get_all_following(&me.id).await;
+    #[tool(
+        description = "Get ALL followers of the authenticated user on X (Twitter). Auto-paginates to fetch every follower. Returns usernames, display names, follower counts, and bios. Set export_path to stream them to a CSV or JSONL file instead and get back only the path and row count."
+    )]
+    async fn get_all_followers(
+        &self,
+        Parameters(params): Parameters<GetAllFollowsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Ok(self.all_follows(params, FollowList::Followers).await)
+    }
 
-        Ok(Self::ok_or_err(
-            result.map(|users| Self::format_all_follows(&users, "following")),
-        ))
+    #[tool(
+        description = "Save a timestamped snapshot of the account's followers and/or following (IDs, handles and names) for follower_changes to compare against. Fetches the full lists, so large accounts take a while."
+    )]
+    async fn snapshot_followers(
+        &self,
+        Parameters(params): Parameters<SnapshotFollowersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (account, _) = try_tool!(self.require_account(params.account.as_deref()));
+        let lists = match params.list {
+            Some(list) => vec![list],
+            None => vec![FollowList::Followers, FollowList::Following],
+        };
+
+        let mut output = String::new();
+        for list in lists {
+            match self.take_snapshot(account, list).await {
+                Ok(snapshot) => output.push_str(&format!(
+                    "Saved {} snapshot for {account}: {} users at {}\n",
+                    list.as_str(),
+                    snapshot.users.len(),
+                    Self::format_unix(snapshot.taken_at)
+                )),
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "{output}Failed to snapshot {}: {e}",
+                        list.as_str()
+                    ))]));
+                }
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     #[tool(
-        description = "Get ALL followers of the authenticated user on X (Twitter). Auto-paginates to fetch every follower. Returns usernames, display names, follower counts, and bios."
+        description = "Compare the latest follower (or following) snapshot with an earlier one: new followers, unfollowers and renamed handles. Users are matched by ID, so a handle change is reported as a rename. Set refresh to take a new snapshot first; set since to pick the earlier snapshot by time."
     )]
-    async fn get_all_followers(
+    async fn follower_changes(
         &self,
-        Parameters(params): Parameters<AccountOnlyParams>,
+        Parameters(params): Parameters<FollowerChangesParams>,
     ) -> Result<CallToolResult, McpError> {
-        let (_account, client, me) =
-            try_tool!(self.require_me_for(params.account.as_deref()).await);
+        let (account, _) = try_tool!(self.require_account(params.account.as_deref()));
+        let list = params.list.unwrap_or(FollowList::Followers);
+        let name = list.as_str();
 
-        let result = client.get_all_followers(&me.id).await;
+        if params.refresh == Some(true)
+            && let Err(e) = self.take_snapshot(account, list).await
+        {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to snapshot {name}: {e}"
+            ))]));
+        }
+        let snapshots = match self.snapshots.list(account, list) {
+            Ok(snapshots) => snapshots,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let Some((latest, earlier)) = snapshots.split_last() else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "No {name} snapshots for {account} yet. Take one with snapshot_followers, or \
+                 add a [follower_snapshots] section to the config to take them on a schedule."
+            ))]));
+        };
+        let baseline = match params.since.as_deref() {
+            Some(raw) => {
+                let bound = try_tool!(
+                    parse_bound(raw, false).map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+                );
+                earlier.iter().rev().find(|s| s.taken_at <= bound).ok_or_else(|| {
+                    format!(
+                        "No {name} snapshot for {account} at or before {raw}. The oldest was \
+                         taken {}.",
+                        Self::format_unix(snapshots[0].taken_at)
+                    )
+                })
+            }
+            None => earlier.last().ok_or_else(|| {
+                format!(
+                    "Only one {name} snapshot for {account} (taken {}). Take another with \
+                     snapshot_followers, or pass refresh=true, to see what changed.",
+                    Self::format_unix(latest.taken_at)
+                )
+            }),
+        };
+        let baseline = match baseline {
+            Ok(baseline) => baseline,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let (old, new) = match self
+            .snapshots
+            .load(account, list, &baseline.id)
+            .and_then(|old| Ok((old, self.snapshots.load(account, list, &latest.id)?)))
+        {
+            Ok(pair) => pair,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
 
-        Ok(Self::ok_or_err(
-            result.map(|users| Self::format_all_follows(&users, "followers")),
-        ))
+        let diff = SnapshotDiff::between(&old, &new);
+        let delta = new.users.len() as i64 - old.users.len() as i64;
+        let (title, added_label, removed_label) = match list {
+            FollowList::Followers => ("Follower changes", "New followers", "Unfollowers"),
+            FollowList::Following => {
+                ("Following changes", "Started following", "Stopped following")
+            }
+        };
+        let mut output = format!(
+            "{title} for {account}: {} -> {} ({delta:+})\nCompared {} with {}\n",
+            old.users.len(),
+            new.users.len(),
+            Self::format_unix(old.taken_at),
+            Self::format_unix(new.taken_at)
+        );
+        Self::push_snapshot_users(&mut output, added_label, &diff.added);
+        Self::push_snapshot_users(&mut output, removed_label, &diff.removed);
+        if !diff.renamed.is_empty() {
+            output.push_str(&format!("\nRenamed handles ({}):\n", diff.renamed.len()));
+            for (before, after) in diff.renamed.iter().take(SNAPSHOT_LIST_LIMIT) {
+                output.push_str(&format!(
+                    "  - @{} -> @{} ({})\n",
+                    before.username, after.username, after.name
+                ));
+            }
+            if diff.renamed.len() > SNAPSHOT_LIST_LIMIT {
+                output.push_str(&format!(
+                    "  ... and {} more\n",
+                    diff.renamed.len() - SNAPSHOT_LIST_LIMIT
+                ));
+            }
+        }
+        if diff.added.is_empty() && diff.removed.is_empty() && diff.renamed.is_empty() {
+            output.push_str("\nNo changes.\n");
+        }
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     #[tool(
@@ -631,9 +3127,9 @@ impl PostXServer {
     )]
     async fn follow_user(
         &self,
-        Parameters(params): Parameters<LookupUserParams>,
+        Parameters(params): Parameters<FollowUserParams>,
     ) -> Result<CallToolResult, McpError> {
-        let (_account, client, me) =
+        let (account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
         let target_id = match client.resolve_user_id(&params.user).await {
@@ -641,6 +3137,15 @@ impl PostXServer {
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
         };
 
+        if params.dry_run == Some(true) {
+            let request = XClient::follow_request(&me.id, &target_id);
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "{}{}",
+                Self::dry_run_header(&account),
+                Self::format_request_preview(&request)
+            ))]));
+        }
+
         Ok(Self::ok_or_err(
             client
                 .follow_user(&me.id, &target_id)
@@ -713,14 +3218,15 @@ impl PostXServer {
     ) -> Result<CallToolResult, McpError> {
         let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
 
-        let (_account, client, me) =
+        let (account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
+        let result = client.like_tweet(&me.id, tweet_id).await;
+        if let Ok(true) = result {
+            self.record_history(HistoryEntry::new(HistoryAction::Like, &account, tweet_id));
+        }
         Ok(Self::ok_or_err(
-            client
-                .like_tweet(&me.id, tweet_id)
-                .await
-                .map(|liked| format!("Tweet {tweet_id} liked: {liked}")),
+            result.map(|liked| format!("Tweet {tweet_id} liked: {liked}")),
         ))
     }
 
@@ -747,17 +3253,27 @@ impl PostXServer {
     )]
     async fn delete_tweet(
         &self,
-        Parameters(params): Parameters<TweetIdParams>,
+        Parameters(params): Parameters<DeleteTweetParams>,
     ) -> Result<CallToolResult, McpError> {
         let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
 
-        let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        if params.dry_run == Some(true) {
+            let request = XClient::delete_tweet_request(tweet_id);
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "{}{}",
+                Self::dry_run_header(account),
+                Self::format_request_preview(&request)
+            ))]));
+        }
 
+        let result = client.delete_tweet(tweet_id).await;
+        if let Ok(true) = result {
+            self.record_history(HistoryEntry::new(HistoryAction::Delete, account, tweet_id));
+        }
         Ok(Self::ok_or_err(
-            client
-                .delete_tweet(tweet_id)
-                .await
-                .map(|deleted| format!("Tweet {tweet_id} deleted: {deleted}")),
+            result.map(|deleted| format!("Tweet {tweet_id} deleted: {deleted}")),
         ))
     }
 
@@ -768,14 +3284,15 @@ impl PostXServer {
     ) -> Result<CallToolResult, McpError> {
         let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
 
-        let (_account, client, me) =
+        let (account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
+        let result = client.retweet(&me.id, tweet_id).await;
+        if let Ok(true) = result {
+            self.record_history(HistoryEntry::new(HistoryAction::Retweet, &account, tweet_id));
+        }
         Ok(Self::ok_or_err(
-            client
-                .retweet(&me.id, tweet_id)
-                .await
-                .map(|retweeted| format!("Tweet {tweet_id} retweeted: {retweeted}")),
+            result.map(|retweeted| format!("Tweet {tweet_id} retweeted: {retweeted}")),
         ))
     }
 
@@ -798,7 +3315,144 @@ impl PostXServer {
     }
 
     #[tool(
-        description = "Search recent tweets on X (Twitter) from the last 7 days. Supports operators: from:user, #hashtag, @mention, \"exact phrase\", -exclude, lang:en, etc."
+        description = "Get one or more tweets on X (Twitter) by ID or URL (up to 100). Returns text, author, metrics, conversation ID, quoted/replied-to tweets, media URLs, links, hashtags and mentions."
+    )]
+    async fn get_tweet(
+        &self,
+        Parameters(params): Parameters<GetTweetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.tweet_ids.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "At least one tweet ID is required.",
+            )]));
+        }
+        if params.tweet_ids.len() > 100 {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Cannot look up more than 100 tweets at once.",
+            )]));
+        }
+
+        let mut ids = Vec::with_capacity(params.tweet_ids.len());
+        for raw in &params.tweet_ids {
+            ids.push(try_tool!(Self::require_tweet_id(raw)));
+        }
+
+        let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        let result = client.get_tweets(&ids).await;
+
+        Ok(Self::ok_or_err(
+            result.map(|r| Self::format_tweet_details(&r.tweets, &r.errors)),
+        ))
+    }
+
+    #[tool(
+        description = "Get the full reply tree of a conversation on X (Twitter) from any tweet in it (ID or URL). Returns an indented tree with authors and metrics. Replies come from recent search (last 7 days)."
+    )]
+    async fn get_conversation(
+        &self,
+        Parameters(params): Parameters<TweetIdParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
+
+        let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        let tweet = match client.get_tweet(tweet_id).await {
+            Ok(t) => t,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let conversation_id = tweet
+            .conversation_id
+            .clone()
+            .unwrap_or_else(|| tweet.id.clone());
+
+        let root = if conversation_id == tweet.id {
+            Some(tweet)
+        } else {
+            match client.get_tweet(&conversation_id).await {
+                Ok(r) => Some(r),
+                Err(e) => {
+                    tracing::warn!("get_conversation: root {conversation_id} unavailable: {e}");
+                    None
+                }
+            }
+        };
+
+        let result = client.search_conversation(&conversation_id, None).await;
+
+        Ok(Self::ok_or_err(result.map(|r| {
+            Self::format_conversation(&conversation_id, root.as_ref(), &r.tweets, r.truncated)
+        })))
+    }
+
+    #[tool(
+        description = "Unroll a self-reply thread on X (Twitter) into clean Markdown with image links. Accepts any tweet in the thread (ID or URL) and follows the original author's chain of self-replies. Optionally writes the Markdown to a file."
+    )]
+    async fn unroll_thread(
+        &self,
+        Parameters(params): Parameters<UnrollThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let tweet_id = try_tool!(Self::require_tweet_id(&params.tweet_id));
+
+        let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        let tweet = match client.get_tweet(tweet_id).await {
+            Ok(t) => t,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let root = match &tweet.conversation_id {
+            Some(conv) if conv != &tweet.id => match client.get_tweet(conv).await {
+                Ok(r) => r,
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            },
+            _ => tweet,
+        };
+        let Some(author) = root.username.clone() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not determine the thread author.",
+            )]));
+        };
+
+        let replies = match client
+            .search_conversation(&root.id, Some(&format!("from:{author}")))
+            .await
+        {
+            Ok(r) => r.tweets,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let chain = Self::collect_self_thread(&root, &replies);
+        let markdown = Self::format_thread_markdown(&chain);
+
+        let mut output = format!("Unrolled {} tweet(s) by @{author}.\n", chain.len());
+        if let Some(path) = params.output_path.as_deref().map(str::trim)
+            && !path.is_empty()
+        {
+            let path = try_tool!(
+                self.resolve_output_path(path, "unrolled")
+                    .map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            );
+            if params.overwrite != Some(true) && path.exists() {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "{} already exists. Pass overwrite: true to replace it, or choose another \
+                     path.",
+                    path.display()
+                ))]));
+            }
+            try_tool!(
+                write_atomic(&path, markdown.as_bytes())
+                    .map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            );
+            output.push_str(&format!("Saved to {}\n", path.display()));
+        }
+        output.push('\n');
+        output.push_str(&markdown);
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Search recent tweets on X (Twitter) from the last 7 days. Supports operators: from:user, #hashtag, @mention, \"exact phrase\", -exclude, lang:en, etc. Set export_path to write every page of results, up to max_rows, to a CSV or JSONL file instead."
     )]
     async fn search_tweets(
         &self,
@@ -813,6 +3467,28 @@ impl PostXServer {
 
         let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
 
+        if let Some(path) = params.export_path.as_deref().map(str::trim)
+            && !path.is_empty()
+        {
+            let exporter = try_tool!(
+                self.create_exporter(path, params.format, params.overwrite)
+                    .map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            );
+            let source = TweetPages::Search {
+                query,
+                sort_order: params.sort_order.as_deref(),
+            };
+            let result = Self::export_tweet_pages(
+                client,
+                source,
+                exporter,
+                params.max_rows,
+                params.pagination_token.clone(),
+            )
+            .await;
+            return Ok(Self::ok_or_err(result));
+        }
+
         let max_results = params.max_results.unwrap_or(10).clamp(10, 100);
 
         let result = client
@@ -830,7 +3506,7 @@ impl PostXServer {
     }
 
     #[tool(
-        description = "Get the authenticated user's home timeline on X (Twitter). Returns recent tweets in reverse chronological order. Can exclude replies and/or retweets."
+        description = "Get the authenticated user's home timeline on X (Twitter). Returns recent tweets in reverse chronological order. Can exclude replies and/or retweets. Set export_path to write every page of tweets, up to max_rows, to a CSV or JSONL file instead."
     )]
     async fn get_timeline(
         &self,
@@ -839,6 +3515,28 @@ impl PostXServer {
         let (_account, client, me) =
             try_tool!(self.require_me_for(params.account.as_deref()).await);
 
+        if let Some(path) = params.export_path.as_deref().map(str::trim)
+            && !path.is_empty()
+        {
+            let exporter = try_tool!(
+                self.create_exporter(path, params.format, params.overwrite)
+                    .map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+            );
+            let source = TweetPages::Timeline {
+                user_id: &me.id,
+                exclude: params.exclude.as_deref(),
+            };
+            let result = Self::export_tweet_pages(
+                &client,
+                source,
+                exporter,
+                params.max_rows,
+                params.pagination_token.clone(),
+            )
+            .await;
+            return Ok(Self::ok_or_err(result));
+        }
+
         let max_results = params.max_results.unwrap_or(20).clamp(1, 100);
 
         let result = client
@@ -895,7 +3593,16 @@ impl PostXServer {
             )]));
         }
 
-        let (_account, client) = try_tool!(self.require_account(params.account.as_deref()));
+        let (account, client) = try_tool!(self.require_account(params.account.as_deref()));
+
+        if params.dry_run == Some(true) {
+            let request = XClient::send_dm_request(conversation_id, text);
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "{}{}",
+                Self::dry_run_header(account),
+                Self::format_request_preview(&request)
+            ))]));
+        }
 
         let result = client.send_dm(conversation_id, text).await;
 
@@ -908,7 +3615,521 @@ impl PostXServer {
     }
 }
 
-#[tool_handler]
+// --- Schedule worker ---
+
+impl PostXServer {
+    /// Spawn the background jobs for the lifetime of the MCP session.
+    pub fn start_background_jobs(&self) {
+        let server = self.clone();
+        tokio::spawn(async move { server.run_background_jobs(std::future::pending()).await });
+    }
+
+    /// Run background jobs (the schedule queue) every `SCHEDULE_TICK` until
+    /// `shutdown` resolves. A job that is already running is finished first.
+    pub async fn run_background_jobs(&self, shutdown: impl std::future::Future<Output = ()>) {
+        tokio::pin!(shutdown);
+        loop {
+            self.run_background_tick().await;
+            tokio::select! {
+                _ = tokio::time::sleep(SCHEDULE_TICK) => {}
+                _ = &mut shutdown => break,
+            }
+        }
+    }
+
+    /// One pass over the background jobs. Every server process and the daemon
+    /// run this loop; the worker lock makes sure only one of them posts at a time.
+    async fn run_background_tick(&self) {
+        let _lock = match FileLock::try_acquire(&self.data_dir.join("worker.lock")) {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                tracing::debug!("Another post-x process is running background jobs");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Skipping background jobs: {e}");
+                return;
+            }
+        };
+        self.recover_interrupted_posts();
+        self.run_recurring();
+        self.run_due_posts().await;
+        self.run_follower_snapshots().await;
+        self.run_engagement_tracking().await;
+    }
+
+    /// Only the worker lock holder posts, so a job left in `posting` while we
+    /// hold the lock means a process died mid-post. It may or may not have gone
+    /// out, so it is failed rather than retried.
+    fn recover_interrupted_posts(&self) {
+        let jobs = self.scheduled.list().unwrap_or_else(|e| {
+            tracing::warn!("Failed to read schedule queue: {e}");
+            Vec::new()
+        });
+        for mut job in jobs {
+            if job.status == ScheduledStatus::Posting {
+                job.status = ScheduledStatus::Failed;
+                job.error = Some(
+                    "Interrupted while posting. Check the account's timeline before \
+                     rescheduling; it was not retried automatically."
+                        .to_string(),
+                );
+                self.save_scheduled(&mut job);
+            }
+        }
+    }
+
+    /// Take the `[follower_snapshots]` snapshots once they come due. Like
+    /// recurring jobs, missed runs collapse into a single late run.
+    async fn run_follower_snapshots(&self) {
+        let Some(config) = self.snapshot_schedule.as_deref() else {
+            return;
+        };
+        if let Err(e) = self.run_snapshot_schedule(config).await {
+            tracing::warn!("Follower snapshots: {e}");
+        }
+    }
+
+    async fn run_snapshot_schedule(&self, config: &SnapshotSchedule) -> Result<(), String> {
+        let now = now_unix();
+        let schedule = config.schedule()?;
+        let mut state = self.snapshot_state.get("schedule")?.unwrap_or_default();
+        if state.schedule != config.schedule_key() {
+            state.schedule = config.schedule_key();
+            state.next_run = schedule.next_after(now);
+            return self.snapshot_state.save("schedule", &state);
+        }
+        let Some(due) = state.next_run.filter(|&due| due <= now) else {
+            return Ok(());
+        };
+
+        let mut accounts = config.accounts.clone();
+        if accounts.is_empty() {
+            accounts = self.clients.keys().cloned().collect();
+            accounts.sort();
+        }
+        let mut taken = 0;
+        let mut failures = Vec::new();
+        for account in &accounts {
+            for &list in &config.lists {
+                match self.take_snapshot(account, list).await {
+                    Ok(snapshot) => {
+                        taken += 1;
+                        tracing::info!(
+                            "Saved {} snapshot for {account}: {} users",
+                            list.as_str(),
+                            snapshot.users.len()
+                        );
+                        if let Some(keep) = config.keep
+                            && let Err(e) = self.snapshots.prune(account, list, keep)
+                        {
+                            tracing::warn!("Failed to prune {account} snapshots: {e}");
+                        }
+                    }
+                    Err(e) => failures.push(format!("{account} {}: {e}", list.as_str())),
+                }
+            }
+        }
+        let mut outcome = format!("took {taken} snapshot(s)");
+        if !failures.is_empty() {
+            outcome.push_str(&format!("; failed: {}", failures.join("; ")));
+            tracing::warn!("Follower snapshots: {outcome}");
+        }
+
+        state.last_run = Some(due);
+        state.last_outcome = Some(outcome);
+        state.next_run = schedule.next_after(now_unix());
+        self.snapshot_state.save("schedule", &state)
+    }
+
+    /// Re-fetch metrics of recently posted tweets every `interval_minutes`.
+    async fn run_engagement_tracking(&self) {
+        let Some(config) = self.engagement.as_deref() else {
+            return;
+        };
+        let now = now_unix();
+        let mut state = match self.engagement_state.get("state") {
+            Ok(state) => state.unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Engagement tracking: {e}");
+                return;
+            }
+        };
+        if state.schedule == config.schedule_key() && state.next_run.is_some_and(|due| due > now) {
+            return;
+        }
+
+        let since = now.saturating_sub(config.track_days * 86_400);
+        let outcome = match self.refresh_metrics(None, since, config.private_metrics).await {
+            Ok(summary) => summary,
+            Err(e) => format!("failed: {e}"),
+        };
+        tracing::info!("Engagement tracking: {outcome}");
+
+        state.schedule = config.schedule_key();
+        state.last_run = Some(now);
+        state.last_outcome = Some(outcome);
+        state.next_run = Some(now + config.interval_minutes * 60);
+        if let Err(e) = self.engagement_state.save("state", &state) {
+            tracing::warn!("Engagement tracking: {e}");
+        }
+    }
+
+    /// Fetch and store current metrics for every tweet in the post history
+    /// posted at or after `since` and not deleted. Returns a one-line summary
+    /// that includes tweets X returned errors for.
+    async fn refresh_metrics(
+        &self,
+        account: Option<&str>,
+        since: u64,
+        private: bool,
+    ) -> Result<String, String> {
+        let posts = self.history.live_posts(account, since)?;
+        let mut by_account: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for post in &posts {
+            by_account
+                .entry(&post.account)
+                .or_default()
+                .push(&post.tweet_id);
+        }
+
+        let mut recorded = 0;
+        let mut problems = Vec::new();
+        for (account, ids) in by_account {
+            let Some(client) = self.clients.get(account) else {
+                problems.push(format!("{account}: account is no longer configured"));
+                continue;
+            };
+            for chunk in ids.chunks(100) {
+                match Self::fetch_metrics(client, chunk, private).await {
+                    Ok(result) => {
+                        let fetched_at = now_unix();
+                        let samples: Vec<MetricsSample> = result
+                            .tweets
+                            .iter()
+                            .map(|t| MetricsSample::from_api(account, t, fetched_at))
+                            .collect();
+                        self.history.record_metrics(&samples)?;
+                        recorded += samples.len();
+                        problems.extend(result.errors.iter().map(|(id, e)| format!("{id}: {e}")));
+                    }
+                    Err(e) => problems.push(format!("{account}: {e}")),
+                }
+            }
+        }
+
+        let mut summary = format!("updated metrics for {recorded} of {} tweet(s)", posts.len());
+        if !problems.is_empty() {
+            summary.push_str(&format!("; {} problem(s): ", problems.len()));
+            summary.push_str(&problems.iter().take(5).cloned().collect::<Vec<_>>().join("; "));
+            if problems.len() > 5 {
+                summary.push_str("; ...");
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Look up metrics for up to 100 tweets. With `private`, tweets X won't
+    /// return private metrics for (older than 30 days, say) are fetched again
+    /// with public metrics only.
+    async fn fetch_metrics(
+        client: &XClient,
+        ids: &[&str],
+        private: bool,
+    ) -> Result<TweetMetricsResult, String> {
+        if !private {
+            return client.get_tweet_metrics(ids, false).await;
+        }
+        let mut result = match client.get_tweet_metrics(ids, true).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::debug!("Private metrics unavailable, using public metrics: {e}");
+                return client.get_tweet_metrics(ids, false).await;
+            }
+        };
+        let retry: Vec<&str> = ids
+            .iter()
+            .copied()
+            .filter(|id| result.errors.iter().any(|(failed, _)| failed == id))
+            .collect();
+        if !retry.is_empty() {
+            let public = client.get_tweet_metrics(&retry, false).await?;
+            result.tweets.extend(public.tweets);
+            result.errors = public.errors;
+        }
+        Ok(result)
+    }
+
+    /// Fetch one of an account's follow lists and store it as a snapshot.
+    async fn take_snapshot(
+        &self,
+        account: &str,
+        list: FollowList,
+    ) -> Result<FollowSnapshot, String> {
+        let (_, client) = self.resolve_account(Some(account))?;
+        let (_, me) = self.ensure_me(Some(account)).await?;
+        let users = match list {
+            FollowList::Followers => client.get_all_followers(&me.id).await?,
+            FollowList::Following => client.get_all_following(&me.id).await?,
+        };
+        let snapshot = FollowSnapshot::new(account, list, now_unix(), &users);
+        self.snapshots.save(&snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// Queue a scheduled post for every recurring job that has come due. Each
+    /// job fires at most once per tick, even if several runs were missed.
+    fn run_recurring(&self) {
+        let now = now_unix();
+        for job in &self.recurring.jobs {
+            if let Err(e) = self.run_recurring_job(job, now) {
+                tracing::warn!("Recurring job {}: {e}", job.name);
+            }
+        }
+    }
+
+    fn run_recurring_job(&self, job: &RecurringJob, now: u64) -> Result<(), String> {
+        let schedule = job.schedule()?;
+        let mut state = self.recurring_state.get(&job.name)?.unwrap_or_default();
+        // A new job, or one whose schedule changed, starts at its next
+        // occurrence rather than firing immediately.
+        if state.schedule != job.schedule_key() {
+            state.schedule = job.schedule_key();
+            state.next_run = schedule.next_after(now);
+            return self.recurring_state.save(&job.name, &state);
+        }
+        let Some(due) = state.next_run.filter(|&due| due <= now) else {
+            return Ok(());
+        };
+
+        let outcome = if job.skip_holidays
+            && let Some(date) = schedule.local_date(due)
+            && self.recurring.holidays.contains(&date)
+        {
+            format!("skipped: {date} is a holiday")
+        } else if now.saturating_sub(due) > MISSED_GRACE_SECS
+            && job.if_missed == MissedPolicy::Skip
+        {
+            format!("skipped: missed by {} minute(s)", (now - due) / 60)
+        } else {
+            match self.queue_recurring(job, due, now) {
+                Ok(id) => format!("queued {id}"),
+                Err(e) => format!("failed: {e}"),
+            }
+        };
+        tracing::info!("Recurring job {}: {outcome}", job.name);
+
+        state.last_run = Some(due);
+        state.last_outcome = Some(outcome);
+        state.next_run = schedule.next_after(now);
+        self.recurring_state.save(&job.name, &state)
+    }
+
+    /// Put one run of a recurring job on the schedule queue, taking the next
+    /// post from its evergreen queue if it has one. Returns the job ID.
+    fn queue_recurring(&self, job: &RecurringJob, due: u64, now: u64) -> Result<String, String> {
+        let (account, _) = self.resolve_account(job.account.as_deref())?;
+        // The evergreen state is saved only once the post is queued, so a failed
+        // queue write doesn't mark a post as used.
+        let mut evergreen_update = None;
+        let (text, media) = match (&job.text, &job.evergreen) {
+            (Some(text), _) => (text.clone(), job.media.clone()),
+            (None, Some(name)) => {
+                let queue = &self.recurring.evergreen[name];
+                let mut state = self.evergreen_state.get(name)?.unwrap_or_default();
+                let index = queue.next_eligible(&state, now).ok_or_else(|| {
+                    format!(
+                        "every post in evergreen queue '{name}' was used in the last {} day(s)",
+                        queue.min_days_between_repeats
+                    )
+                })?;
+                let post = &queue.posts[index];
+                state.last_used.insert(post.key(), now);
+                state.last_key = Some(post.key());
+                evergreen_update = Some((name, state));
+                (post.text().to_string(), post.media().to_vec())
+            }
+            (None, None) => return Err("no text or evergreen queue configured".to_string()),
+        };
+        let scheduled = ScheduledPost {
+            id: new_id("sched"),
+            account: account.to_string(),
+            content: ScheduledContent::Tweet {
+                text,
+                media,
+                reply_to: None,
+            },
+            post_at: due,
+            // Lateness was already handled above; post as soon as it's picked up.
+            if_missed: MissedPolicy::PostLate,
+            status: ScheduledStatus::Pending,
+            posted: Vec::new(),
+            error: None,
+            thread_job_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.scheduled.save(&scheduled.id, &scheduled)?;
+        if let Some((name, state)) = evergreen_update
+            && let Err(e) = self.evergreen_state.save(name, &state)
+        {
+            tracing::warn!("Failed to save evergreen queue '{name}' state: {e}");
+        }
+        Ok(scheduled.id)
+    }
+
+    async fn run_due_posts(&self) {
+        let now = now_unix();
+        let mut due: Vec<ScheduledPost> = match self.scheduled.list() {
+            Ok(jobs) => jobs
+                .into_iter()
+                .filter(|j| j.status == ScheduledStatus::Pending && j.post_at <= now)
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to read schedule queue: {e}");
+                return;
+            }
+        };
+        due.sort_by_key(|j| j.post_at);
+
+        for job in due {
+            // Re-read so a cancel since the listing is honoured.
+            match self.scheduled.load(&job.id) {
+                Ok(mut job) if job.status == ScheduledStatus::Pending => {
+                    self.publish_scheduled(&mut job).await;
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn publish_scheduled(&self, job: &mut ScheduledPost) {
+        let late_by = now_unix().saturating_sub(job.post_at);
+        if late_by > MISSED_GRACE_SECS && job.if_missed == MissedPolicy::Skip {
+            job.status = ScheduledStatus::Skipped;
+            job.error = Some(format!(
+                "Missed by {} minute(s) and skipped (if_missed = skip)",
+                late_by / 60
+            ));
+            self.save_scheduled(job);
+            tracing::info!("Skipped missed scheduled post {}", job.id);
+            return;
+        }
+
+        job.status = ScheduledStatus::Posting;
+        self.save_scheduled(job);
+        let started_at = chrono::Utc::now();
+        let outcome = match self.post_scheduled_content(job).await {
+            Ok(()) => {
+                job.status = ScheduledStatus::Posted;
+                tracing::info!("Posted scheduled post {} as {}", job.id, job.account);
+                let urls: Vec<&str> = job.posted.iter().map(|p| p.url.as_str()).collect();
+                CallToolResult::success(vec![Content::text(format!("Posted: {}", urls.join(" ")))])
+            }
+            Err(e) => {
+                job.status = ScheduledStatus::Failed;
+                tracing::warn!("Scheduled post {} failed: {e}", job.id);
+                let outcome = CallToolResult::error(vec![Content::text(e.clone())]);
+                job.error = Some(e);
+                outcome
+            }
+        };
+        self.save_scheduled(job);
+        self.audit(
+            "schedule_worker",
+            serde_json::json!({ "job_id": job.id, "account": job.account }),
+            started_at,
+            &Ok(outcome),
+        );
+    }
+
+    async fn post_scheduled_content(&self, job: &mut ScheduledPost) -> Result<(), String> {
+        let (account, client, me) = self
+            .require_me_for(Some(&job.account))
+            .await
+            .map_err(|e| Self::result_text(&e))?;
+
+        match &job.content {
+            ScheduledContent::Tweet {
+                text,
+                media,
+                reply_to,
+            } => {
+                let post = client
+                    .post_tweet(text, media, None, reply_to.as_deref(), None, &me.username)
+                    .await?;
+                let mut entry = HistoryEntry::new(HistoryAction::Tweet, &account, &post.tweet_id);
+                entry.url = Some(post.url.clone());
+                entry.text = Some(post.text.clone());
+                entry.media = media.iter().map(|m| m.path.clone()).collect();
+                entry.reply_to = reply_to.clone();
+                self.record_history(entry);
+                job.posted = vec![PostedTweet {
+                    tweet_id: post.tweet_id,
+                    url: post.url,
+                }];
+                Ok(())
+            }
+            ScheduledContent::Thread { tweets, reply_to } => {
+                let tweets: Vec<(String, Vec<MediaAttachment>)> = tweets
+                    .iter()
+                    .map(|t| (t.text.clone(), t.media.clone()))
+                    .collect();
+                let reply_to = reply_to.clone();
+                let (thread_job, result, _claim) = self
+                    .post_thread_job(&account, &client, &me.username, &tweets, reply_to.as_deref())
+                    .await;
+                job.thread_job_id = Some(thread_job.id);
+                job.posted = thread_job.posted;
+                match result.error {
+                    Some(e) => Err(e),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+// --- Audit ---
+
+impl PostXServer {
+    /// Append a call to the audit log, if one is configured. `tool` is the tool
+    /// name, or a label like "cli:thread" for writes that don't come from a tool call.
+    pub fn audit(
+        &self,
+        tool: &str,
+        arguments: serde_json::Value,
+        started_at: chrono::DateTime<chrono::Utc>,
+        result: &Result<CallToolResult, McpError>,
+    ) {
+        let Some(log) = &self.audit else {
+            return;
+        };
+        let account = match arguments.get("account").and_then(|a| a.as_str()) {
+            Some(account) if !account.trim().is_empty() => Some(account.trim().to_string()),
+            // These act on an account stored elsewhere (draft, job, file) or on several.
+            _ if matches!(
+                tool,
+                "post_to_accounts"
+                    | "post_thread_from_file"
+                    | "publish_draft"
+                    | "resume_thread"
+                    | "cancel_scheduled"
+                    | "cli:thread"
+            ) =>
+            {
+                None
+            }
+            _ => Some(self.default_account.clone()),
+        };
+        let (is_error, text) = match result {
+            Ok(result) => (result.is_error == Some(true), Self::result_text(result)),
+            Err(e) => (true, e.message.to_string()),
+        };
+        log.record(tool, account.as_deref(), arguments, started_at, is_error, &text);
+    }
+}
+
 impl ServerHandler for PostXServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
@@ -918,4 +4139,42 @@ impl ServerHandler for PostXServer {
             ))
             .with_instructions(&self.instructions)
     }
+
+    // Written out rather than generated by `#[tool_handler]` so that every
+    // mutating call passes through the audit log.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let audited = self.audit.is_some() && AuditLog::is_audited(&request.name);
+        let tool = request.name.to_string();
+        let arguments = audited
+            .then(|| serde_json::Value::Object(request.arguments.clone().unwrap_or_default()));
+        let started_at = chrono::Utc::now();
+
+        let tcc = ToolCallContext::new(self, request, context);
+        let result = self.tool_router.call(tcc).await;
+
+        if let Some(arguments) = arguments {
+            self.audit(&tool, arguments, started_at, &result);
+        }
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
 }
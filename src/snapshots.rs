@@ -0,0 +1,309 @@
+use crate::api::UserSummary;
+use crate::params::FollowList;
+use crate::recurring::{Schedule, default_timezone, is_valid_name};
+use crate::store::{JsonStore, new_id};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// --- Config ---
+
+/// `[follower_snapshots]` config section: take snapshots on a cron schedule.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotSchedule {
+    /// Standard 5-field cron expression, e.g. `"0 6 * * *"` for daily at 06:00.
+    pub cron: String,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Accounts to snapshot (default: every configured account).
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// Lists to snapshot (default: followers and following).
+    #[serde(default = "default_lists")]
+    pub lists: Vec<FollowList>,
+    /// Snapshots to keep per account and list; older ones are deleted.
+    /// Unset keeps all.
+    pub keep: Option<usize>,
+}
+
+fn default_lists() -> Vec<FollowList> {
+    vec![FollowList::Followers, FollowList::Following]
+}
+
+impl SnapshotSchedule {
+    pub fn validate(&self, accounts: &[&str]) -> Result<(), String> {
+        self.schedule()?;
+        for account in &self.accounts {
+            if !accounts.contains(&account.as_str()) {
+                return Err(format!("unknown account '{account}'"));
+            }
+        }
+        if self.keep == Some(0) {
+            return Err("keep must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn schedule(&self) -> Result<Schedule, String> {
+        Schedule::parse(&self.cron, &self.timezone)
+    }
+
+    /// Identifies the schedule, so saved state is reset when the config changes.
+    pub fn schedule_key(&self) -> String {
+        format!("{} {}", self.cron, self.timezone)
+    }
+}
+
+// --- Snapshots ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotUser {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+}
+
+/// One account's followers or following at a point in time.
+#[derive(Serialize, Deserialize)]
+pub struct FollowSnapshot {
+    pub account: String,
+    pub list: FollowList,
+    pub taken_at: u64,
+    pub users: Vec<SnapshotUser>,
+}
+
+impl FollowSnapshot {
+    pub fn new(account: &str, list: FollowList, taken_at: u64, users: &[UserSummary]) -> Self {
+        Self {
+            account: account.to_string(),
+            list,
+            taken_at,
+            users: users
+                .iter()
+                .map(|u| SnapshotUser {
+                    id: u.id.clone(),
+                    username: u.username.clone(),
+                    name: u.name.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A stored snapshot: its ID, and when it was taken as recorded in the file.
+pub struct SnapshotEntry {
+    pub id: String,
+    pub taken_at: u64,
+}
+
+/// The part of a snapshot file `list` needs, without holding every user.
+#[derive(Serialize, Deserialize)]
+struct SnapshotHeader {
+    taken_at: u64,
+}
+
+/// Snapshots stored as `<dir>/<account>/<list>/<id>.json`.
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn store<T: Serialize + DeserializeOwned>(
+        &self,
+        account: &str,
+        list: FollowList,
+    ) -> Result<JsonStore<T>, String> {
+        // Account names become directory names.
+        if !is_valid_name(account) {
+            return Err(format!(
+                "Can't store snapshots for account '{account}': account names used with \
+                 snapshots may only contain letters, digits, '-' and '_'"
+            ));
+        }
+        Ok(JsonStore::new(self.dir.join(account).join(list.as_str())))
+    }
+
+    /// Store a snapshot under a new unique ID, which is returned.
+    pub fn save(&self, snapshot: &FollowSnapshot) -> Result<String, String> {
+        let id = new_id("snap");
+        self.store(&snapshot.account, snapshot.list)?
+            .save(&id, snapshot)?;
+        Ok(id)
+    }
+
+    /// Every stored snapshot, oldest first. Unreadable files are skipped.
+    pub fn list(&self, account: &str, list: FollowList) -> Result<Vec<SnapshotEntry>, String> {
+        let store = self.store::<SnapshotHeader>(account, list)?;
+        let mut entries: Vec<SnapshotEntry> = store
+            .ids()?
+            .into_iter()
+            .filter_map(|id| match store.load(&id) {
+                Ok(header) => Some(SnapshotEntry {
+                    id,
+                    taken_at: header.taken_at,
+                }),
+                Err(e) => {
+                    tracing::warn!("Skipping {account} {} snapshot {id}: {e}", list.as_str());
+                    None
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.taken_at.cmp(&b.taken_at).then_with(|| a.id.cmp(&b.id)));
+        Ok(entries)
+    }
+
+    pub fn load(
+        &self,
+        account: &str,
+        list: FollowList,
+        id: &str,
+    ) -> Result<FollowSnapshot, String> {
+        self.store(account, list)?.load(id)
+    }
+
+    /// Delete all but the newest `keep` snapshots. Returns how many were deleted.
+    pub fn prune(&self, account: &str, list: FollowList, keep: usize) -> Result<usize, String> {
+        let store = self.store::<FollowSnapshot>(account, list)?;
+        let entries = self.list(account, list)?;
+        let excess = entries.len().saturating_sub(keep);
+        for entry in &entries[..excess] {
+            store.delete(&entry.id)?;
+        }
+        Ok(excess)
+    }
+}
+
+// --- Diff ---
+
+/// What changed between two snapshots of the same list. Users are matched by
+/// ID, so a changed handle is a rename rather than an unfollow and a follow.
+#[derive(Default)]
+pub struct SnapshotDiff {
+    pub added: Vec<SnapshotUser>,
+    pub removed: Vec<SnapshotUser>,
+    /// (before, after)
+    pub renamed: Vec<(SnapshotUser, SnapshotUser)>,
+}
+
+impl SnapshotDiff {
+    pub fn between(old: &FollowSnapshot, new: &FollowSnapshot) -> Self {
+        let before: HashMap<&str, &SnapshotUser> =
+            old.users.iter().map(|u| (u.id.as_str(), u)).collect();
+        let after: HashMap<&str, &SnapshotUser> =
+            new.users.iter().map(|u| (u.id.as_str(), u)).collect();
+
+        let mut diff = Self::default();
+        for user in &new.users {
+            match before.get(user.id.as_str()) {
+                None => diff.added.push(user.clone()),
+                Some(prev) if !prev.username.eq_ignore_ascii_case(&user.username) => {
+                    diff.renamed.push(((*prev).clone(), user.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .users
+            .iter()
+            .filter(|u| !after.contains_key(u.id.as_str()))
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, username: &str) -> SnapshotUser {
+        SnapshotUser {
+            id: id.to_string(),
+            username: username.to_string(),
+            name: username.to_uppercase(),
+        }
+    }
+
+    fn snapshot(taken_at: u64, users: Vec<SnapshotUser>) -> FollowSnapshot {
+        FollowSnapshot {
+            account: "main".to_string(),
+            list: FollowList::Followers,
+            taken_at,
+            users,
+        }
+    }
+
+    fn usernames(users: &[SnapshotUser]) -> Vec<&str> {
+        users.iter().map(|u| u.username.as_str()).collect()
+    }
+
+    #[test]
+    fn reports_added_and_removed_users() {
+        let old = snapshot(1, vec![user("1", "alice"), user("2", "bob")]);
+        let new = snapshot(2, vec![user("2", "bob"), user("3", "carol")]);
+        let diff = SnapshotDiff::between(&old, &new);
+        assert_eq!(usernames(&diff.added), ["carol"]);
+        assert_eq!(usernames(&diff.removed), ["alice"]);
+        assert!(diff.renamed.is_empty());
+    }
+
+    #[test]
+    fn changed_handle_is_a_rename() {
+        let old = snapshot(1, vec![user("1", "alice"), user("2", "bob")]);
+        let new = snapshot(2, vec![user("1", "alice_dev"), user("2", "bob")]);
+        let diff = SnapshotDiff::between(&old, &new);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        let [(before, after)] = diff.renamed.as_slice() else {
+            panic!("expected one rename");
+        };
+        assert_eq!(
+            (before.username.as_str(), after.username.as_str()),
+            ("alice", "alice_dev")
+        );
+    }
+
+    #[test]
+    fn handle_case_change_is_not_a_rename() {
+        let old = snapshot(1, vec![user("1", "alice")]);
+        let new = snapshot(2, vec![user("1", "Alice")]);
+        assert!(SnapshotDiff::between(&old, &new).renamed.is_empty());
+    }
+
+    #[test]
+    fn handle_reused_by_another_account_is_not_a_rename() {
+        // @alice left and a different account took the handle.
+        let old = snapshot(1, vec![user("1", "alice")]);
+        let new = snapshot(2, vec![user("9", "alice")]);
+        let diff = SnapshotDiff::between(&old, &new);
+        assert_eq!(diff.added[0].id, "9");
+        assert_eq!(diff.removed[0].id, "1");
+        assert!(diff.renamed.is_empty());
+    }
+
+    #[test]
+    fn snapshots_in_the_same_second_are_kept_apart() {
+        let dir = std::env::temp_dir().join(format!("post-x-snapshots-{}", new_id("test")));
+        let store = SnapshotStore::new(dir.clone());
+        let first = store
+            .save(&snapshot(100, vec![user("1", "alice")]))
+            .unwrap();
+        let second = store.save(&snapshot(100, vec![user("2", "bob")])).unwrap();
+        let older = store.save(&snapshot(50, Vec::new())).unwrap();
+        let entries = store.list("main", FollowList::Followers).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_ne!(first, second);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            (entries[0].id.as_str(), entries[0].taken_at),
+            (older.as_str(), 50)
+        );
+        assert!(entries[1..].iter().all(|e| e.taken_at == 100));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::api::MediaAttachment;
+use crate::params::MissedPolicy;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// --- Generic JSON file store ---
+
+/// A directory of `<id>.json` files, one record per file. Writes go through a
+/// temp file and rename so a crash never leaves a half-written record.
+pub struct JsonStore<T> {
+    dir: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> JsonStore<T> {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            _marker: PhantomData,
+        }
+    }
+
+    fn path_for(&self, id: &str) -> Result<PathBuf, String> {
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("Invalid ID '{id}'"));
+        }
+        Ok(self.dir.join(format!("{id}.json")))
+    }
+
+    pub fn save(&self, id: &str, record: &T) -> Result<(), String> {
+        let path = self.path_for(id)?;
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {e}", self.dir.display()))?;
+        let json = serde_json::to_string_pretty(record)
+            .map_err(|e| format!("Failed to serialize {id}: {e}"))?;
+        write_atomic(&path, json.as_bytes())
+    }
+
+    pub fn load(&self, id: &str) -> Result<T, String> {
+        self.get(id)?.ok_or_else(|| format!("'{id}' not found"))
+    }
+
+    /// Every record in the store. Files that can't be read or parsed are logged and skipped.
+    pub fn list(&self) -> Result<Vec<T>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {e}", self.dir.display())),
+        };
+        let mut records = Vec::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(record) => records.push(record),
+                Err(e) => tracing::warn!("Skipping {}: {e}", path.display()),
+            }
+        }
+        Ok(records)
+    }
+
+    /// IDs of every record, sorted, without reading the files.
+    pub fn ids(&self) -> Result<Vec<String>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {e}", self.dir.display())),
+        };
+        let mut ids: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Remove a record. Returns `false` if it didn't exist.
+    pub fn delete(&self, id: &str) -> Result<bool, String> {
+        let path = self.path_for(id)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(format!("Failed to delete {}: {e}", path.display())),
+        }
+    }
+
+    /// Like `load`, but a missing record is `Ok(None)` rather than an error.
+    pub fn get(&self, id: &str) -> Result<Option<T>, String> {
+        let path = self.path_for(id)?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+    }
+}
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)
+        .map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// An exclusive advisory lock on a file, released when dropped (or when the
+/// process exits, however it exits).
+pub struct FileLock {
+    file: std::fs::File,
+}
+
+impl FileLock {
+    /// Take the lock, or `Ok(None)` if another process holds it.
+    pub fn try_acquire(path: &Path) -> Result<Option<Self>, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { file })),
+            Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+            Err(std::fs::TryLockError::Error(e)) => {
+                Err(format!("Failed to lock {}: {e}", path.display()))
+            }
+        }
+    }
+
+    /// Record the holder's PID in the lock file, for `kill $(cat daemon.lock)`.
+    pub fn write_pid(&mut self) -> Result<(), String> {
+        use std::io::Write;
+        self.file
+            .set_len(0)
+            .and_then(|_| writeln!(self.file, "{}", std::process::id()))
+            .map_err(|e| format!("Failed to write lock file: {e}"))
+    }
+}
+
+pub fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Short unique ID like `thread-1718000000-3fa2c1`.
+pub fn new_id(prefix: &str) -> String {
+    let suffix: u32 = rand::thread_rng().gen_range(0..0x0100_0000);
+    format!("{prefix}-{}-{suffix:06x}", now_unix())
+}
+
+// --- Thread jobs ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadJobStatus {
+    InProgress,
+    Failed,
+    Completed,
+    /// Failed, and every posted tweet was deleted (or a delete was attempted).
+    RolledBack,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ThreadJobTweet {
+    pub text: String,
+    #[serde(default)]
+    pub media: Vec<MediaAttachment>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PostedTweet {
+    pub tweet_id: String,
+    pub url: String,
+}
+
+/// A thread posting job, persisted so a partially posted thread can be resumed.
+#[derive(Serialize, Deserialize)]
+pub struct ThreadJob {
+    pub id: String,
+    pub account: String,
+    pub tweets: Vec<ThreadJobTweet>,
+    pub reply_to: Option<String>,
+    pub posted: Vec<PostedTweet>,
+    pub status: ThreadJobStatus,
+    /// Index into `tweets` of the tweet that failed, if any.
+    pub failed_index: Option<usize>,
+    pub error: Option<String>,
+    /// IDs of posted tweets that were deleted by a rollback.
+    #[serde(default)]
+    pub rolled_back: Vec<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl ThreadJob {
+    /// Where the next tweet should attach: the last posted tweet, or the thread's root reply target.
+    pub fn next_reply_to(&self) -> Option<&str> {
+        self.posted
+            .last()
+            .map(|p| p.tweet_id.as_str())
+            .or(self.reply_to.as_deref())
+    }
+}
+
+// --- Scheduled posts ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledStatus {
+    Pending,
+    /// Picked up by the worker. A job still in this state after a restart was
+    /// interrupted mid-post and is marked failed rather than retried.
+    Posting,
+    Posted,
+    Failed,
+    /// Missed its time while nothing was running, with `if_missed = skip`.
+    Skipped,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduledContent {
+    Tweet {
+        text: String,
+        #[serde(default)]
+        media: Vec<MediaAttachment>,
+        reply_to: Option<String>,
+    },
+    Thread {
+        tweets: Vec<ThreadJobTweet>,
+        reply_to: Option<String>,
+    },
+}
+
+/// A tweet or thread waiting in the schedule queue, and its outcome once run.
+#[derive(Serialize, Deserialize)]
+pub struct ScheduledPost {
+    pub id: String,
+    pub account: String,
+    pub content: ScheduledContent,
+    /// Unix time the post is due.
+    pub post_at: u64,
+    pub if_missed: MissedPolicy,
+    pub status: ScheduledStatus,
+    #[serde(default)]
+    pub posted: Vec<PostedTweet>,
+    pub error: Option<String>,
+    /// Thread job created when a scheduled thread was posted, for `resume_thread`.
+    pub thread_job_id: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+// --- Drafts ---
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DraftContent {
+    Tweet {
+        text: String,
+        #[serde(default)]
+        media: Vec<MediaAttachment>,
+        quote_tweet_id: Option<String>,
+    },
+    Thread {
+        tweets: Vec<ThreadJobTweet>,
+    },
+}
+
+/// A tweet or thread staged for review. Removed once it is published.
+#[derive(Serialize, Deserialize)]
+pub struct Draft {
+    pub id: String,
+    pub account: String,
+    pub content: DraftContent,
+    pub reply_to: Option<String>,
+    /// Free-form note for the reviewer, never posted.
+    pub note: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
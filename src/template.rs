@@ -0,0 +1,213 @@
+use crate::api::MediaAttachment;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// A named tweet template from the `[templates.<name>]` config section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TweetTemplate {
+    /// Tweet text with `{placeholder}` variables. `{{` and `}}` are literal braces.
+    pub text: String,
+    /// Media attached when the caller doesn't pass any.
+    #[serde(default)]
+    pub media: Vec<MediaAttachment>,
+    /// Values used for placeholders the caller doesn't set.
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    /// Append the account's `signature` to the rendered text.
+    #[serde(default = "default_true")]
+    pub signature: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+impl TweetTemplate {
+    /// Placeholder names in order of first use, without duplicates.
+    pub fn placeholders(&self) -> Result<Vec<&str>, String> {
+        let mut seen = BTreeSet::new();
+        Ok(parse(&self.text)?
+            .into_iter()
+            .filter_map(|s| match s {
+                Segment::Placeholder(name) if seen.insert(name) => Some(name),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Fill in every placeholder and append the signature. Missing and unknown
+    /// variables are both errors, so a typo never posts a half-rendered tweet.
+    pub fn render(
+        &self,
+        variables: &HashMap<String, String>,
+        signature: Option<&str>,
+    ) -> Result<String, String> {
+        let placeholders = self.placeholders()?;
+
+        let mut unknown: Vec<&str> = variables
+            .keys()
+            .map(String::as_str)
+            .filter(|k| !placeholders.contains(k))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            return Err(format!(
+                "Unknown variable(s): {}. Template placeholders: {}",
+                unknown.join(", "),
+                placeholders.join(", ")
+            ));
+        }
+
+        let missing: Vec<&str> = placeholders
+            .iter()
+            .copied()
+            .filter(|p| !variables.contains_key(*p) && !self.defaults.contains_key(*p))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing variable(s): {}", missing.join(", ")));
+        }
+
+        let mut text = String::new();
+        for segment in parse(&self.text)? {
+            match segment {
+                Segment::Literal(s) => text.push_str(s),
+                Segment::Placeholder(name) => {
+                    text.push_str(variables.get(name).or(self.defaults.get(name)).unwrap())
+                }
+            }
+        }
+        let mut text = text.trim().to_string();
+        if self.signature
+            && let Some(signature) = signature.filter(|s| !s.trim().is_empty())
+        {
+            text.push_str("\n\n");
+            text.push_str(signature.trim());
+        }
+        Ok(text)
+    }
+}
+
+fn parse(text: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(i) = rest.find(['{', '}']) {
+        if i > 0 {
+            segments.push(Segment::Literal(&rest[..i]));
+        }
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            segments.push(Segment::Literal(&tail[..1]));
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err("Unmatched '}' in template text (use '}}' for a literal brace)".into());
+        }
+        let end = tail
+            .find('}')
+            .ok_or("Unclosed '{' in template text (use '{{' for a literal brace)")?;
+        let name = tail[1..end].trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "Invalid placeholder '{}': names may only contain letters, digits and '_'",
+                &tail[..=end]
+            ));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &tail[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(text: &str) -> TweetTemplate {
+        TweetTemplate {
+            text: text.to_string(),
+            media: Vec::new(),
+            defaults: HashMap::new(),
+            signature: true,
+        }
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn fills_placeholders_and_appends_signature() {
+        let t = template("New release: {version} — {url}");
+        let text = t
+            .render(
+                &vars(&[("version", "1.2"), ("url", "https://x.y")]),
+                Some("— Team"),
+            )
+            .unwrap();
+        assert_eq!(text, "New release: 1.2 — https://x.y\n\n— Team");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        let t = template("Use {{name}} in {lang}: {{ }}");
+        assert_eq!(t.placeholders().unwrap(), ["lang"]);
+        let text = t.render(&vars(&[("lang", "Rust")]), None).unwrap();
+        assert_eq!(text, "Use {name} in Rust: { }");
+    }
+
+    #[test]
+    fn placeholders_are_listed_once_in_order() {
+        let t = template("{b} {a} {b}");
+        assert_eq!(t.placeholders().unwrap(), ["b", "a"]);
+    }
+
+    #[test]
+    fn defaults_fill_missing_variables() {
+        let mut t = template("Hi {who}");
+        t.defaults.insert("who".into(), "all".into());
+        assert_eq!(t.render(&HashMap::new(), None).unwrap(), "Hi all");
+        assert_eq!(t.render(&vars(&[("who", "you")]), None).unwrap(), "Hi you");
+    }
+
+    #[test]
+    fn missing_variable_is_an_error() {
+        let err = template("{a} and {b}")
+            .render(&vars(&[("a", "1")]), None)
+            .unwrap_err();
+        assert_eq!(err, "Missing variable(s): b");
+    }
+
+    #[test]
+    fn unknown_variable_is_an_error() {
+        let err = template("{a}")
+            .render(&vars(&[("a", "1"), ("typo", "2")]), None)
+            .unwrap_err();
+        assert!(err.starts_with("Unknown variable(s): typo."), "{err}");
+    }
+
+    #[test]
+    fn signature_can_be_turned_off() {
+        let mut t = template("Plain");
+        t.signature = false;
+        assert_eq!(t.render(&HashMap::new(), Some("sig")).unwrap(), "Plain");
+    }
+
+    #[test]
+    fn stray_braces_are_errors() {
+        assert!(template("oops }").placeholders().is_err());
+        assert!(template("oops {name").placeholders().is_err());
+        assert!(template("{bad-name}").placeholders().is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+// --- Config ---
+
+/// Per-account text normalization applied before a tweet is posted.
+/// Every step is off unless enabled under `[accounts.<name>.normalize]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NormalizeConfig {
+    /// Replace em dashes and en dashes with "-".
+    pub fold_dashes: bool,
+    /// Replace curly single/double quotes with straight ASCII quotes.
+    pub fold_quotes: bool,
+    /// Collapse runs of spaces, strip trailing spaces and squeeze blank lines.
+    pub collapse_whitespace: bool,
+    /// Apply Unicode NFC normalization.
+    pub nfc: bool,
+    /// Drop trailing hashtags that already appear earlier in the text.
+    pub dedupe_hashtags: bool,
+}
+
+pub struct NormalizedText {
+    pub text: String,
+    /// Human-readable description of every transformation that changed the text.
+    pub applied: Vec<String>,
+}
+
+// --- Pipeline ---
+
+pub fn normalize(text: &str, config: &NormalizeConfig) -> NormalizedText {
+    let mut text = text.to_string();
+    let mut applied = Vec::new();
+
+    if config.nfc {
+        let normalized: String = text.nfc().collect();
+        if normalized != text {
+            applied.push("nfc: applied Unicode NFC normalization".to_string());
+            text = normalized;
+        }
+    }
+
+    if config.fold_dashes {
+        let (folded, count) = fold_chars(&text, &['\u{2014}', '\u{2013}'], |_| '-');
+        if count > 0 {
+            applied.push(format!("dashes: replaced {count} em/en dash(es) with '-'"));
+            text = folded;
+        }
+    }
+
+    if config.fold_quotes {
+        let (folded, count) = fold_chars(
+            &text,
+            &[
+                '\u{2018}', '\u{2019}', '\u{201A}', '\u{201B}', '\u{201C}', '\u{201D}', '\u{201E}',
+                '\u{201F}',
+            ],
+            |c| {
+                if matches!(c, '\u{2018}'..='\u{201B}') {
+                    '\''
+                } else {
+                    '"'
+                }
+            },
+        );
+        if count > 0 {
+            applied.push(format!(
+                "quotes: replaced {count} smart quote(s) with straight quotes"
+            ));
+            text = folded;
+        }
+    }
+
+    if config.collapse_whitespace {
+        let collapsed = collapse_whitespace(&text);
+        if collapsed != text {
+            applied.push("whitespace: collapsed redundant spaces and blank lines".to_string());
+            text = collapsed;
+        }
+    }
+
+    if config.dedupe_hashtags {
+        let (deduped, removed) = dedupe_trailing_hashtags(&text);
+        if !removed.is_empty() {
+            applied.push(format!(
+                "hashtags: removed duplicate trailing hashtag(s) {}",
+                removed.join(", ")
+            ));
+            text = deduped;
+        }
+    }
+
+    NormalizedText { text, applied }
+}
+
+// --- Length ---
+
+/// Length every URL counts as after X wraps it in a t.co link.
+const URL_WEIGHT: usize = 23;
+
+/// Approximate X's weighted tweet length: URLs count as 23, characters in the
+/// Latin and common punctuation ranges count as 1, everything else (CJK,
+/// emoji) counts as 2. Emoji modifiers, variation selectors and characters
+/// joined by a zero-width joiner add nothing.
+pub fn weighted_length(text: &str) -> usize {
+    let mut total = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let word = token.trim_end_matches(char::is_whitespace);
+        let (counted, whitespace) = token.split_at(word.len());
+        total += whitespace.chars().count();
+        if counted.starts_with("https://") || counted.starts_with("http://") {
+            total += URL_WEIGHT;
+            continue;
+        }
+        let mut joined = false;
+        for c in counted.chars() {
+            let cp = c as u32;
+            if joined || matches!(cp, 0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF) {
+                joined = false;
+                continue;
+            }
+            if cp == 0x200D {
+                joined = true;
+                continue;
+            }
+            total += match cp {
+                0..=4351 | 8192..=8205 | 8208..=8223 | 8242..=8247 => 1,
+                _ => 2,
+            };
+        }
+    }
+    total
+}
+
+/// Reject text that is empty or longer than `max_length` weighted characters.
+pub fn validate_tweet_text(text: &str, max_length: usize) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Tweet text cannot be empty".into());
+    }
+    let length = weighted_length(text);
+    if length > max_length {
+        return Err(format!(
+            "Tweet is too long: {length}/{max_length} weighted characters"
+        ));
+    }
+    Ok(())
+}
+
+// --- Steps ---
+
+fn fold_chars(text: &str, targets: &[char], replace: impl Fn(char) -> char) -> (String, usize) {
+    let mut count = 0;
+    let folded = text
+        .chars()
+        .map(|c| {
+            if targets.contains(&c) {
+                count += 1;
+                replace(c)
+            } else {
+                c
+            }
+        })
+        .collect();
+    (folded, count)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut blank_run = 0;
+
+    for line in text.lines() {
+        let collapsed = line
+            .split(|c: char| c.is_whitespace())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if collapsed.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        lines.push(collapsed);
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+fn is_hashtag(word: &str) -> bool {
+    word.len() > 1
+        && word.starts_with('#')
+        && word[1..].chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Lowercased tag name of a word that starts with a hashtag, ignoring trailing punctuation.
+fn hashtag_key(word: &str) -> Option<String> {
+    let tag: String = word
+        .strip_prefix('#')?
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
+/// Removes hashtags from the trailing block of hashtags when the same tag
+/// (case-insensitive) already appears in the body or earlier in the block.
+fn dedupe_trailing_hashtags(text: &str) -> (String, Vec<String>) {
+    let trimmed = text.trim_end();
+    let mut body = trimmed;
+    loop {
+        let (head, last) = match body.rfind(char::is_whitespace) {
+            Some(i) => (&body[..i], body[i..].trim_start()),
+            None => ("", body),
+        };
+        if !is_hashtag(last) {
+            break;
+        }
+        body = head.trim_end();
+        if body.is_empty() {
+            break;
+        }
+    }
+
+    let block = trimmed[body.len()..].trim_start();
+    if block.is_empty() {
+        return (text.to_string(), Vec::new());
+    }
+    let separator = &trimmed[body.len()..trimmed.len() - block.len()];
+
+    let mut seen: HashSet<String> = body.split_whitespace().filter_map(hashtag_key).collect();
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    for tag in block.split_whitespace() {
+        if hashtag_key(tag).is_some_and(|key| seen.insert(key)) {
+            kept.push(tag);
+        } else {
+            removed.push(tag.to_string());
+        }
+    }
+
+    if removed.is_empty() {
+        return (text.to_string(), removed);
+    }
+    let result = if kept.is_empty() {
+        body.to_string()
+    } else {
+        format!("{body}{separator}{}", kept.join(" "))
+    };
+    (result, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_steps() -> NormalizeConfig {
+        NormalizeConfig {
+            fold_dashes: true,
+            fold_quotes: true,
+            collapse_whitespace: true,
+            nfc: true,
+            dedupe_hashtags: true,
+        }
+    }
+
+    #[test]
+    fn default_config_leaves_text_alone() {
+        let text = "Smart \u{201C}quotes\u{201D} \u{2014} and   spaces #a #a";
+        let result = normalize(text, &NormalizeConfig::default());
+        assert_eq!(result.text, text);
+        assert!(result.applied.is_empty());
+    }
+
+    #[test]
+    fn folds_dashes_and_quotes() {
+        let result = normalize(
+            "It\u{2019}s \u{201C}done\u{201D} \u{2014} 1\u{2013}2",
+            &all_steps(),
+        );
+        assert_eq!(result.text, "It's \"done\" - 1-2");
+        assert!(result.applied.iter().any(|a| a.contains("2 em/en dash")));
+        assert!(result.applied.iter().any(|a| a.contains("3 smart quote")));
+    }
+
+    #[test]
+    fn collapses_whitespace_and_blank_lines() {
+        let result = normalize("  one   two  \n\n\n\nthree \n", &all_steps());
+        assert_eq!(result.text, "one two\n\nthree");
+    }
+
+    #[test]
+    fn applies_nfc() {
+        let result = normalize("cafe\u{301}", &all_steps());
+        assert_eq!(result.text, "caf\u{e9}");
+        assert!(result.applied[0].starts_with("nfc"));
+    }
+
+    #[test]
+    fn unchanged_text_reports_nothing() {
+        assert!(
+            normalize("plain text #tag", &all_steps())
+                .applied
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn dedupes_trailing_hashtags_case_insensitively() {
+        let (text, removed) = dedupe_trailing_hashtags("Launching today #rust #Rust #launch");
+        assert_eq!(text, "Launching today #rust #launch");
+        assert_eq!(removed, ["#Rust"]);
+    }
+
+    #[test]
+    fn dedupes_hashtags_already_in_body() {
+        let (text, removed) = dedupe_trailing_hashtags("Love #Rust.\n\n#rust #dev");
+        assert_eq!(text, "Love #Rust.\n\n#dev");
+        assert_eq!(removed, ["#rust"]);
+    }
+
+    #[test]
+    fn drops_whole_block_when_every_tag_repeats() {
+        let (text, removed) = dedupe_trailing_hashtags("Ship #it\n#it");
+        assert_eq!(text, "Ship #it");
+        assert_eq!(removed, ["#it"]);
+    }
+
+    #[test]
+    fn text_of_only_hashtags_keeps_first_of_each() {
+        let (text, removed) = dedupe_trailing_hashtags("#a #b #A");
+        assert_eq!(text, "#a #b");
+        assert_eq!(removed, ["#A"]);
+    }
+
+    #[test]
+    fn unique_trailing_tags_are_kept() {
+        let text = "#a is better than #b, says #c";
+        let (result, removed) = dedupe_trailing_hashtags(text);
+        assert_eq!(result, text);
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn latin_text_counts_one_per_char() {
+        assert_eq!(weighted_length("hello"), 5);
+        assert_eq!(weighted_length("a \u{2014} b"), 5);
+    }
+
+    #[test]
+    fn urls_count_as_23() {
+        assert_eq!(
+            weighted_length("Check https://example.com/x ok"),
+            6 + 23 + 3
+        );
+        assert_eq!(
+            weighted_length("http://a.b/very/long/path/that/keeps/going/on"),
+            23
+        );
+    }
+
+    #[test]
+    fn cjk_counts_two_per_char() {
+        assert_eq!(weighted_length("日本語"), 6);
+    }
+
+    #[test]
+    fn emoji_sequences_count_as_one_emoji() {
+        assert_eq!(weighted_length("👍"), 2);
+        assert_eq!(weighted_length("👍🏽"), 2);
+        assert_eq!(weighted_length("❤\u{FE0F}"), 2);
+        assert_eq!(weighted_length("👨\u{200D}👩\u{200D}👧"), 2);
+        assert_eq!(weighted_length("hi 👨\u{200D}👩\u{200D}👧!"), 3 + 2 + 1);
+    }
+}
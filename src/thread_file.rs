@@ -0,0 +1,228 @@
+use crate::api::MediaAttachment;
+use std::path::Path;
+
+/// A thread drafted as Markdown: optional front-matter, then tweets separated
+/// by `---` lines. Images written as `![alt text](path)` become media
+/// attachments, with paths resolved relative to the Markdown file.
+pub struct ThreadFile {
+    pub account: Option<String>,
+    pub reply_to: Option<String>,
+    pub tweets: Vec<(String, Vec<MediaAttachment>)>,
+}
+
+pub fn load(path: &Path) -> Result<ThreadFile, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    parse(&content, base_dir).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+pub fn parse(content: &str, base_dir: &Path) -> Result<ThreadFile, String> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+    let mut lines = content.lines().peekable();
+    let mut file = ThreadFile {
+        account: None,
+        reply_to: None,
+        tweets: Vec::new(),
+    };
+
+    // --- Front-matter ---
+
+    if lines.peek().is_some_and(|l| l.trim_end() == "---") {
+        lines.next();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line == "---" {
+                closed = true;
+                break;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(':').ok_or_else(|| {
+                format!("Invalid front-matter line '{line}', expected 'key: value'")
+            })?;
+            let value = unquote(value.trim());
+            let value = (!value.is_empty()).then(|| value.to_string());
+            match key.trim() {
+                "account" => file.account = value,
+                "reply_to" => file.reply_to = value,
+                other => {
+                    return Err(format!(
+                        "Unknown front-matter key '{other}' (supported: account, reply_to)"
+                    ));
+                }
+            }
+        }
+        if !closed {
+            return Err("Front-matter is missing its closing '---' line".to_string());
+        }
+    }
+
+    // --- Tweets ---
+
+    let mut chunk: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && line.trim_end() == "---" {
+            push_tweet(&mut file.tweets, &chunk, base_dir)?;
+            chunk.clear();
+        } else {
+            chunk.push(line);
+        }
+    }
+    push_tweet(&mut file.tweets, &chunk, base_dir)?;
+
+    if file.tweets.is_empty() {
+        return Err("No tweets found. Separate tweets with a line containing only '---'".into());
+    }
+    Ok(file)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Turn one `---`-delimited chunk into a tweet, pulling out image references.
+/// Chunks that are empty after trimming (e.g. a trailing separator) are skipped.
+fn push_tweet(
+    tweets: &mut Vec<(String, Vec<MediaAttachment>)>,
+    lines: &[&str],
+    base_dir: &Path,
+) -> Result<(), String> {
+    let mut media = Vec::new();
+    let mut text_lines = Vec::new();
+    for line in lines {
+        let (stripped, found) = extract_images(line);
+        for (alt, path) in found {
+            let resolved = if Path::new(&path).is_absolute() {
+                path
+            } else {
+                base_dir.join(&path).to_string_lossy().into_owned()
+            };
+            media.push(MediaAttachment {
+                path: resolved,
+                alt_text: (!alt.trim().is_empty()).then(|| alt.trim().to_string()),
+            });
+        }
+        // A line that held only images should not leave a blank line behind.
+        if stripped.trim().is_empty() && !line.trim().is_empty() {
+            continue;
+        }
+        text_lines.push(stripped.trim_end().to_string());
+    }
+
+    let text = text_lines.join("\n").trim().to_string();
+    if text.is_empty() && media.is_empty() {
+        return Ok(());
+    }
+    tweets.push((text, media));
+    Ok(())
+}
+
+/// Remove every `![alt](path)` from a line, returning the remaining text and
+/// the `(alt, path)` pairs in order. Malformed references are left as text.
+fn extract_images(line: &str) -> (String, Vec<(String, String)>) {
+    let mut text = String::new();
+    let mut images = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("![") {
+        let after = &rest[start + 2..];
+        let parsed = after.find("](").and_then(|alt_end| {
+            let path_part = &after[alt_end + 2..];
+            path_part
+                .find(')')
+                .map(|path_end| (alt_end, &path_part[..path_end], path_end))
+        });
+        let Some((alt_end, path, path_end)) = parsed else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        images.push((after[..alt_end].to_string(), path.trim().to_string()));
+        rest = &after[alt_end + 2 + path_end + 1..];
+        if text.ends_with(' ') {
+            rest = rest.trim_start_matches(' ');
+        }
+    }
+    text.push_str(rest);
+    (text, images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(file: &ThreadFile) -> Vec<&str> {
+        file.tweets.iter().map(|(text, _)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn splits_on_separator_lines_after_front_matter() {
+        let content = "---\naccount: main\nreply_to: \"123\"\n---\nFirst\n\n---\nSecond\n---\n";
+        let file = parse(content, Path::new("/drafts")).unwrap();
+        assert_eq!(file.account.as_deref(), Some("main"));
+        assert_eq!(file.reply_to.as_deref(), Some("123"));
+        assert_eq!(texts(&file), ["First", "Second"]);
+    }
+
+    #[test]
+    fn separator_inside_code_fence_is_text() {
+        let content = "Config:\n```yaml\n---\nkey: value\n```\n---\nNext";
+        let file = parse(content, Path::new("/drafts")).unwrap();
+        assert_eq!(
+            texts(&file),
+            ["Config:\n```yaml\n---\nkey: value\n```", "Next"]
+        );
+    }
+
+    #[test]
+    fn images_become_media_resolved_against_the_file() {
+        let content = "Look ![a chart](img/chart.png) here\n![](/abs/photo.jpg)\nDone";
+        let file = parse(content, Path::new("/drafts")).unwrap();
+        let (text, media) = &file.tweets[0];
+        assert_eq!(text, "Look here\nDone");
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0].path, "/drafts/img/chart.png");
+        assert_eq!(media[0].alt_text.as_deref(), Some("a chart"));
+        assert_eq!(media[1].path, "/abs/photo.jpg");
+        assert_eq!(media[1].alt_text, None);
+    }
+
+    #[test]
+    fn malformed_image_is_left_as_text() {
+        let (text, images) = extract_images("See ![alt](missing-paren");
+        assert_eq!(text, "See ![alt](missing-paren");
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn unclosed_front_matter_is_an_error() {
+        let err = parse("---\naccount: main\n", Path::new(".")).err().unwrap();
+        assert!(err.contains("closing '---'"), "{err}");
+    }
+
+    #[test]
+    fn unknown_front_matter_key_is_an_error() {
+        let err = parse("---\nschedule: now\n---\nHi", Path::new("."))
+            .err()
+            .unwrap();
+        assert!(err.contains("Unknown front-matter key 'schedule'"), "{err}");
+    }
+
+    #[test]
+    fn only_separators_is_an_error() {
+        assert!(parse("---\n---\n\n---\n", Path::new(".")).is_err());
+    }
+}